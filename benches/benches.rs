@@ -82,6 +82,17 @@ fn try_alloc_try_with_err<T, E: Default>(n: usize) {
     }
 }
 
+fn alloc_min_align<T: Default, const MIN_ALIGN: usize>(n: usize) {
+    let arena = bumpalo::Bump::<bumpalo::Global, MIN_ALIGN>::with_min_align_and_capacity(
+        n * std::mem::size_of::<T>(),
+    );
+    for _ in 0..n {
+        let arena = black_box(&arena);
+        let val: &mut T = arena.alloc(black_box(Default::default()));
+        black_box(val);
+    }
+}
+
 #[cfg(feature = "collections")]
 fn format_realloc(bump: &bumpalo::Bump, n: usize) {
     let n = criterion::black_box(n);
@@ -344,6 +355,25 @@ fn bench_try_alloc_try_with_err(c: &mut Criterion) {
     });
 }
 
+fn bench_alloc_min_align(c: &mut Criterion) {
+    // With a `MIN_ALIGN` of 8, allocating `u64`s never needs to round the bump
+    // finger down, whereas the default `MIN_ALIGN` of 1 always does.
+    let mut group = c.benchmark_group("alloc-min-align");
+    group.throughput(Throughput::Elements(ALLOCATIONS as u64));
+    group.bench_function("u64, min align 1", |b| {
+        b.iter(|| alloc_min_align::<u64, 1>(ALLOCATIONS))
+    });
+    group.bench_function("u64, min align 8", |b| {
+        b.iter(|| alloc_min_align::<u64, 8>(ALLOCATIONS))
+    });
+    group.bench_function("small, min align 1", |b| {
+        b.iter(|| alloc_min_align::<Small, 1>(ALLOCATIONS))
+    });
+    group.bench_function("small, min align 8", |b| {
+        b.iter(|| alloc_min_align::<Small, 8>(ALLOCATIONS))
+    });
+}
+
 fn bench_format_realloc(c: &mut Criterion) {
     let mut group = c.benchmark_group("format-realloc");
 
@@ -401,6 +431,7 @@ criterion_group!(
     bench_try_alloc_with,
     bench_try_alloc_try_with,
     bench_try_alloc_try_with_err,
+    bench_alloc_min_align,
     bench_format_realloc,
     bench_string_from_str_in,
     bench_string_push_str
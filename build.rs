@@ -0,0 +1,16 @@
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-check-cfg=cfg(bumpalo_asan)");
+
+    // The `sanitize` feature talks to AddressSanitizer only when it is linked
+    // in, which `cfg(sanitize = "address")` would tell us if it were stable.
+    let sanitize = env::var_os("CARGO_FEATURE_SANITIZE").is_some();
+    let asan = env::var("CARGO_CFG_SANITIZE")
+        .map(|sanitizers| sanitizers.split(',').any(|s| s == "address"))
+        .unwrap_or(false);
+    if sanitize && asan {
+        println!("cargo:rustc-cfg=bumpalo_asan");
+    }
+}
@@ -99,21 +99,126 @@ fn size_align<T>() -> (usize, usize) {
 }
 
 /// The `AllocErr` error indicates an allocation failure
-/// that may be due to resource exhaustion or to
-/// something wrong when combining the given input arguments with this
+/// that may be due to resource exhaustion, to an arena's allocation limit, or
+/// to something wrong when combining the given input arguments with this
 /// allocator.
+///
+/// Its [`kind`][AllocErr::kind] tells these apart, and it also reports the
+/// [`Layout`] that could not be allocated and how much
+/// [`headroom`][AllocErr::headroom] the arena had left under its allocation
+/// limit.
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::{AllocErrKind, Bump};
+///
+/// let bump = Bump::new();
+/// bump.set_allocation_limit(Some(0));
+///
+/// let err = bump.try_alloc([0_u8; 64]).unwrap_err();
+/// assert_eq!(err.kind(), AllocErrKind::AllocationLimit);
+/// assert_eq!(err.layout().unwrap().size(), 64);
+/// assert_eq!(err.headroom(), Some(0));
+/// ```
 // #[unstable(feature = "allocator_api", issue = "32838")]
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub struct AllocErr;
+pub struct AllocErr {
+    kind: AllocErrKind,
+    layout: Option<Layout>,
+    headroom: Option<usize>,
+}
+
+/// Why an allocation failed, as reported by [`AllocErr::kind`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[non_exhaustive]
+pub enum AllocErrKind {
+    /// Getting more memory would have taken the arena over the limit set
+    /// with [`Bump::set_allocation_limit`][crate::Bump::set_allocation_limit].
+    AllocationLimit,
+    /// Getting more memory would have overdrawn the arena's
+    /// [`AllocationBudget`](struct.AllocationBudget.html), which is only
+    /// available with the `std` feature.
+    AllocationBudget,
+    /// The backing allocator failed to allocate more memory.
+    Allocator,
+    /// The size of the requested allocation overflowed.
+    SizeOverflow,
+    /// The request is valid, but not one that the arena supports, like
+    /// shrinking an allocation to an alignment that it doesn't already have.
+    Unsupported,
+}
+
+impl AllocErr {
+    pub(crate) fn new(kind: AllocErrKind, layout: Option<Layout>, headroom: Option<usize>) -> Self {
+        AllocErr {
+            kind,
+            layout,
+            headroom,
+        }
+    }
+
+    pub(crate) fn size_overflow() -> Self {
+        Self::new(AllocErrKind::SizeOverflow, None, None)
+    }
+
+    pub(crate) fn unsupported(layout: Layout) -> Self {
+        Self::new(AllocErrKind::Unsupported, Some(layout), None)
+    }
+
+    /// Why the allocation failed.
+    pub fn kind(&self) -> AllocErrKind {
+        self.kind
+    }
+
+    /// The layout of the allocation that failed, if it had a valid one.
+    ///
+    /// This is `None` when computing the layout itself overflowed.
+    pub fn layout(&self) -> Option<Layout> {
+        self.layout
+    }
+
+    /// How many more bytes the arena could have gotten from its backing
+    /// allocator before hitting its allocation limit, when the allocation
+    /// failed.
+    ///
+    /// This is `None` if the arena has no allocation limit.
+    pub fn headroom(&self) -> Option<usize> {
+        self.headroom
+    }
+}
 
 // (we need this for downstream impl of trait Error)
 // #[unstable(feature = "allocator_api", issue = "32838")]
 impl fmt::Display for AllocErr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("memory allocation failed")
+        f.write_str("memory allocation")?;
+        if let Some(layout) = self.layout {
+            write!(f, " of {} bytes", layout.size())?;
+        }
+        match self.kind {
+            AllocErrKind::AllocationLimit => {
+                f.write_str(" failed: it would exceed the allocation limit")?;
+                if let Some(headroom) = self.headroom {
+                    write!(f, " ({} bytes of headroom left)", headroom)?;
+                }
+                Ok(())
+            }
+            AllocErrKind::AllocationBudget => {
+                f.write_str(" failed: it would overdraw the allocation budget")
+            }
+            AllocErrKind::Allocator => {
+                f.write_str(" failed: the backing allocator is out of memory")
+            }
+            AllocErrKind::SizeOverflow => f.write_str(" failed: the requested size overflowed"),
+            AllocErrKind::Unsupported => f.write_str(" failed: the request is not supported"),
+        }
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for AllocErr {}
+
 /// The `CannotReallocInPlace` error is used when `grow_in_place` or
 /// `shrink_in_place` were unable to reuse the given memory block for
 /// a requested layout.
@@ -633,7 +738,7 @@ pub unsafe trait Alloc {
         if k.size() > 0 {
             unsafe { self.alloc(k).map(|p| p.cast()) }
         } else {
-            Err(AllocErr)
+            Err(AllocErr::unsupported(k))
         }
     }
 
@@ -702,7 +807,8 @@ pub unsafe trait Alloc {
     {
         match Layout::array::<T>(n) {
             Ok(layout) if layout.size() > 0 => unsafe { self.alloc(layout).map(|p| p.cast()) },
-            _ => Err(AllocErr),
+            Ok(layout) => Err(AllocErr::unsupported(layout)),
+            Err(_) => Err(AllocErr::size_overflow()),
         }
     }
 
@@ -755,7 +861,8 @@ pub unsafe trait Alloc {
                 self.realloc(ptr.cast(), *k_old, k_new.size())
                     .map(NonNull::cast)
             }
-            _ => Err(AllocErr),
+            (Ok(_), Ok(k_new)) => Err(AllocErr::unsupported(k_new)),
+            _ => Err(AllocErr::size_overflow()),
         }
     }
 
@@ -788,7 +895,8 @@ pub unsafe trait Alloc {
                 self.dealloc(ptr.cast(), k);
                 Ok(())
             }
-            _ => Err(AllocErr),
+            Ok(k) => Err(AllocErr::unsupported(k)),
+            Err(_) => Err(AllocErr::size_overflow()),
         }
     }
 }
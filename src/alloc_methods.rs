@@ -0,0 +1,162 @@
+//! The allocation methods that `SyncBump` and `VmBump` share.
+
+/// Define `alloc`, `alloc_slice_copy`, `alloc_str`, `alloc_layout` and the
+/// fallible versions of the first three in an arena's inherent `impl` block,
+/// in terms of the arena's own `try_alloc_layout` method.
+///
+/// `$arena` is the arena type's name, as it is used in the doc examples.
+macro_rules! basic_alloc_methods {
+    ($arena:literal) => {
+        /// Allocate an object in this arena and return an exclusive reference to
+        /// it.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if reserving space for `T` fails.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        #[doc = concat!("let bump = bumpalo::", $arena, "::new();")]
+        /// let x = bump.alloc("hello");
+        /// assert_eq!(*x, "hello");
+        /// ```
+        #[inline(always)]
+        pub fn alloc<T>(&self, val: T) -> &mut T {
+            self.try_alloc(val).unwrap_or_else(|_| $crate::oom())
+        }
+
+        /// Try to allocate an object in this arena and return an exclusive
+        /// reference to it.
+        ///
+        /// ## Errors
+        ///
+        /// Errors if reserving space for `T` fails.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        #[doc = concat!("let bump = bumpalo::", $arena, "::new();")]
+        /// let x = bump.try_alloc("hello");
+        /// assert_eq!(x, Ok(&mut "hello"));
+        /// ```
+        #[inline(always)]
+        pub fn try_alloc<T>(&self, val: T) -> Result<&mut T, $crate::AllocErr> {
+            let p = self
+                .try_alloc_layout(::core::alloc::Layout::new::<T>())?
+                .cast::<T>();
+            unsafe {
+                ::core::ptr::write(p.as_ptr(), val);
+                Ok(&mut *p.as_ptr())
+            }
+        }
+
+        /// `Copy` a slice into this arena and return an exclusive reference to
+        /// the copy.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if reserving space for the slice fails.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        #[doc = concat!("let bump = bumpalo::", $arena, "::new();")]
+        /// let x = bump.alloc_slice_copy(&[1, 2, 3]);
+        /// assert_eq!(x, &[1, 2, 3]);
+        /// ```
+        #[inline(always)]
+        pub fn alloc_slice_copy<T>(&self, src: &[T]) -> &mut [T]
+        where
+            T: Copy,
+        {
+            self.try_alloc_slice_copy(src)
+                .unwrap_or_else(|_| $crate::oom())
+        }
+
+        /// Try to `Copy` a slice into this arena and return an exclusive
+        /// reference to the copy.
+        ///
+        /// ## Errors
+        ///
+        /// Errors if reserving space for the slice fails.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        #[doc = concat!("let bump = bumpalo::", $arena, "::new();")]
+        /// let x = bump.try_alloc_slice_copy(&[1, 2, 3]).unwrap();
+        /// assert_eq!(x, &[1, 2, 3]);
+        /// ```
+        #[inline(always)]
+        pub fn try_alloc_slice_copy<T>(&self, src: &[T]) -> Result<&mut [T], $crate::AllocErr>
+        where
+            T: Copy,
+        {
+            let layout = ::core::alloc::Layout::for_value(src);
+            let dst = self.try_alloc_layout(layout)?.cast::<T>();
+
+            unsafe {
+                ::core::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), src.len());
+                Ok(::core::slice::from_raw_parts_mut(dst.as_ptr(), src.len()))
+            }
+        }
+
+        /// `Copy` a string slice into this arena and return an exclusive
+        /// reference to it.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if reserving space for the string fails.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        #[doc = concat!("let bump = bumpalo::", $arena, "::new();")]
+        /// let hello = bump.alloc_str("hello world");
+        /// assert_eq!("hello world", hello);
+        /// ```
+        #[inline(always)]
+        pub fn alloc_str(&self, src: &str) -> &mut str {
+            self.try_alloc_str(src).unwrap_or_else(|_| $crate::oom())
+        }
+
+        /// Try to `Copy` a string slice into this arena and return an exclusive
+        /// reference to it.
+        ///
+        /// ## Errors
+        ///
+        /// Errors if reserving space for the string fails.
+        ///
+        /// ## Example
+        ///
+        /// ```
+        #[doc = concat!("let bump = bumpalo::", $arena, "::new();")]
+        /// let hello = bump.try_alloc_str("hello world").unwrap();
+        /// assert_eq!("hello world", hello);
+        /// ```
+        #[inline(always)]
+        pub fn try_alloc_str(&self, src: &str) -> Result<&mut str, $crate::AllocErr> {
+            let buffer = self.try_alloc_slice_copy(src.as_bytes())?;
+            unsafe {
+                // This is OK, because it already came in as str, so it is guaranteed to be utf8
+                Ok(::core::str::from_utf8_unchecked_mut(buffer))
+            }
+        }
+
+        /// Allocate space for an object with the given `Layout`.
+        ///
+        /// The returned pointer points at uninitialized memory, and should be
+        /// initialized with
+        /// [`std::ptr::write`](https://doc.rust-lang.org/std/ptr/fn.write.html).
+        ///
+        /// # Panics
+        ///
+        /// Panics if reserving space matching `layout` fails.
+        #[inline(always)]
+        pub fn alloc_layout(&self, layout: ::core::alloc::Layout) -> ::core::ptr::NonNull<u8> {
+            self.try_alloc_layout(layout)
+                .unwrap_or_else(|_| $crate::oom())
+        }
+    };
+}
@@ -0,0 +1,195 @@
+//! A budget of chunk memory that several arenas draw from.
+//!
+//! See [`AllocationBudget`] for details.
+//!
+//! [`AllocationBudget`]: ../struct.AllocationBudget.html
+
+#[cfg(feature = "std")]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+/// A number of bytes that several [`Bump`]s share, and that they get their
+/// chunks of memory from.
+///
+/// An [allocation limit][crate::Bump::set_allocation_limit] caps how much
+/// memory a single arena allocates. An `AllocationBudget` caps how much memory
+/// a group of arenas allocates all together, such as the arenas that handle
+/// different phases of the same request. Cloning a budget creates another
+/// handle to the same budget.
+///
+/// Give an arena a budget with
+/// [`Bump::set_allocation_budget`][crate::Bump::set_allocation_budget] or
+/// [`BumpBuilder::allocation_budget`][crate::BumpBuilder::allocation_budget].
+/// Every chunk that the arena allocates from then on is drawn from the
+/// budget, and allocations that would need a chunk that doesn't fit in what
+/// is left of it fail with
+/// [`AllocErrKind::AllocationBudget`][crate::AllocErrKind::AllocationBudget].
+/// Chunks are returned to the budget when the arena frees them, on
+/// [`reset`][crate::Bump::reset] or when it is dropped.
+///
+/// Chunks count towards the budget with their full size, including
+/// `bumpalo`'s own metadata. The budget is shared through atomic operations,
+/// so arenas on different threads can share it too.
+///
+/// This type is only available with the `std` cargo feature.
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::{AllocErrKind, AllocationBudget, Bump};
+///
+/// let budget = AllocationBudget::new(64 * 1024);
+///
+/// let mut parse = Bump::new();
+/// parse.set_allocation_budget(Some(budget.clone()));
+/// let mut plan = Bump::new();
+/// plan.set_allocation_budget(Some(budget.clone()));
+///
+/// parse.alloc([0_u8; 40 * 1024]);
+/// assert!(budget.used() > 40 * 1024);
+///
+/// // There isn't enough left in the budget for the other arena.
+/// let err = plan.try_alloc([0_u8; 40 * 1024]).unwrap_err();
+/// assert_eq!(err.kind(), AllocErrKind::AllocationBudget);
+///
+/// // Resetting the first arena gives its memory back.
+/// parse.reset_with(bumpalo::RetainPolicy::UpTo(0));
+/// assert_eq!(budget.used(), 0);
+/// assert!(plan.try_alloc([0_u8; 40 * 1024]).is_ok());
+/// ```
+///
+/// [`Bump`]: struct.Bump.html
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct AllocationBudget {
+    inner: Arc<BudgetInner>,
+}
+
+#[cfg(feature = "std")]
+struct BudgetInner {
+    limit: usize,
+    used: AtomicUsize,
+}
+
+#[cfg(feature = "std")]
+impl AllocationBudget {
+    /// Create a new budget of `limit` bytes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let budget = bumpalo::AllocationBudget::new(1024);
+    /// assert_eq!(budget.remaining(), 1024);
+    /// ```
+    pub fn new(limit: usize) -> Self {
+        AllocationBudget {
+            inner: Arc::new(BudgetInner {
+                limit,
+                used: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Get the number of bytes in this budget.
+    pub fn limit(&self) -> usize {
+        self.inner.limit
+    }
+
+    /// Get the number of bytes that arenas have drawn from this budget and
+    /// not returned yet.
+    ///
+    /// This can be more than the [`limit`][AllocationBudget::limit] when
+    /// arenas that already had chunks were given this budget.
+    pub fn used(&self) -> usize {
+        self.inner.used.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of bytes that are left in this budget.
+    pub fn remaining(&self) -> usize {
+        self.limit().saturating_sub(self.used())
+    }
+
+    /// Draw `bytes` from this budget, if that many are left.
+    fn try_charge(&self, bytes: usize) -> bool {
+        self.inner
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(bytes)
+                    .filter(|&used| used <= self.inner.limit)
+            })
+            .is_ok()
+    }
+
+    /// Draw `bytes` from this budget, even if fewer are left.
+    fn charge(&self, bytes: usize) {
+        self.inner.used.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Return `bytes` that were drawn from this budget.
+    fn refund(&self, bytes: usize) {
+        self.inner.used.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for AllocationBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AllocationBudget")
+            .field("limit", &self.limit())
+            .field("used", &self.used())
+            .finish()
+    }
+}
+
+// The budget that a `Bump` draws its chunks from, if any. Without the `std`
+// feature, there are no budgets and this is empty.
+#[derive(Debug, Default)]
+pub(crate) struct BudgetSlot {
+    #[cfg(feature = "std")]
+    budget: Option<AllocationBudget>,
+}
+
+impl BudgetSlot {
+    /// Draw a chunk of `bytes` from the budget, if there is one. Returns
+    /// whether the chunk fits in the budget.
+    #[inline]
+    pub(crate) fn try_charge(&self, bytes: usize) -> bool {
+        #[cfg(feature = "std")]
+        if let Some(budget) = &self.budget {
+            return budget.try_charge(bytes);
+        }
+        let _ = bytes;
+        true
+    }
+
+    /// Return a chunk of `bytes` to the budget, if there is one.
+    #[inline]
+    pub(crate) fn refund(&self, bytes: usize) {
+        #[cfg(feature = "std")]
+        if let Some(budget) = &self.budget {
+            budget.refund(bytes);
+        }
+        let _ = bytes;
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn get(&self) -> Option<&AllocationBudget> {
+        self.budget.as_ref()
+    }
+
+    /// Replace the budget, moving the `bytes` of the arena's existing chunks
+    /// from the old budget to the new one.
+    #[cfg(feature = "std")]
+    pub(crate) fn replace(&mut self, budget: Option<AllocationBudget>, bytes: usize) {
+        if let Some(old) = &self.budget {
+            old.refund(bytes);
+        }
+        if let Some(new) = &budget {
+            new.charge(bytes);
+        }
+        self.budget = budget;
+    }
+}
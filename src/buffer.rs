@@ -0,0 +1,212 @@
+//! Bump allocating out of a caller-provided buffer.
+//!
+//! See [`Bump::from_buffer`] for details.
+//!
+//! [`Bump::from_buffer`]: ../struct.Bump.html#method.from_buffer
+
+use crate::{
+    round_down_to, round_up_to, BackingAllocator, Bump, ChunkPolicy, CHUNK_ALIGN, EMPTY_CHUNK,
+    FOOTER_SIZE,
+};
+use allocator_api2::alloc::AllocError as BackingAllocError;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr::NonNull;
+use core_alloc::alloc::Layout;
+
+/// A backing allocator that never allocates anything.
+///
+/// This is the default fallback of a [`Bump`] created with
+/// [`Bump::from_buffer`]: once its buffer is full, allocating fails with an
+/// [`AllocErr`][crate::AllocErr] instead of getting more memory from
+/// somewhere else.
+///
+/// [`Bump`]: ../struct.Bump.html
+/// [`Bump::from_buffer`]: ../struct.Bump.html#method.from_buffer
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoAlloc;
+
+unsafe impl BackingAllocator for NoAlloc {
+    #[inline]
+    fn allocate(&self, _layout: Layout) -> Result<NonNull<[u8]>, BackingAllocError> {
+        Err(BackingAllocError)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {
+        // Nothing was ever allocated, so there's nothing to deallocate.
+    }
+}
+
+/// The backing allocator of a [`Bump`] that was created over a
+/// caller-provided buffer with [`Bump::from_buffer`] or
+/// [`Bump::from_buffer_in`].
+///
+/// The buffer is the arena's first chunk, and is never handed back to any
+/// allocator. Any further chunks come from the fallback allocator `A`, which
+/// by default is [`NoAlloc`] and never provides any.
+///
+/// [`Bump`]: ../struct.Bump.html
+/// [`Bump::from_buffer`]: ../struct.Bump.html#method.from_buffer
+/// [`Bump::from_buffer_in`]: ../struct.Bump.html#method.from_buffer_in
+#[derive(Debug)]
+pub struct Buffer<'a, A = NoAlloc> {
+    // The start of the chunk that lives in the buffer, if the buffer was big
+    // enough for one.
+    chunk: Option<NonNull<u8>>,
+    fallback: A,
+    buffer: PhantomData<&'a mut [MaybeUninit<u8>]>,
+}
+
+// The chunk pointer is only ever compared against, never dereferenced.
+unsafe impl<A: Send> Send for Buffer<'_, A> {}
+unsafe impl<A: Sync> Sync for Buffer<'_, A> {}
+
+impl<A> Buffer<'_, A> {
+    /// Get a reference to the allocator that chunks are allocated from once
+    /// the buffer is full.
+    pub fn fallback(&self) -> &A {
+        &self.fallback
+    }
+}
+
+unsafe impl<A: BackingAllocator> BackingAllocator for Buffer<'_, A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, BackingAllocError> {
+        self.fallback.allocate(layout)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // The buffer's chunk is borrowed, not allocated.
+        if Some(ptr) != self.chunk {
+            self.fallback.deallocate(ptr, layout);
+        }
+    }
+}
+
+impl<'a> Bump<Buffer<'a>> {
+    /// Construct a new arena that bump allocates into the given buffer, and
+    /// nowhere else.
+    ///
+    /// The buffer becomes the arena's only chunk. Once it is full, allocating
+    /// fails instead of allocating a new chunk, so the arena never touches
+    /// the global allocator. This makes it usable in contexts where
+    /// allocating is not allowed, such as signal handlers.
+    ///
+    /// Note that `bumpalo` always depends on the `alloc` crate, so a program
+    /// that uses it still has to link a global allocator, even if all of its
+    /// arenas are over buffers and never call into it. Targets without the
+    /// `alloc` crate aren't supported.
+    ///
+    /// Part of the buffer is used for `bumpalo`'s own bookkeeping, as with any
+    /// other chunk, and some more may be lost to aligning it. If the buffer is
+    /// too small to hold anything at all, every allocation fails.
+    ///
+    /// See [`from_buffer_in`][Bump::from_buffer_in] for a version that falls
+    /// back to allocating new chunks once the buffer is full.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::Bump;
+    /// use core::mem::MaybeUninit;
+    ///
+    /// let mut buffer = [MaybeUninit::uninit(); 1024];
+    /// let bump = Bump::from_buffer(&mut buffer);
+    ///
+    /// let x = bump.alloc(42_u64);
+    /// assert_eq!(*x, 42);
+    ///
+    /// // Allocations that don't fit in the buffer fail.
+    /// assert!(bump.try_alloc([0_u8; 1024]).is_err());
+    /// ```
+    pub fn from_buffer(buffer: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self::from_buffer_in(buffer, NoAlloc)
+    }
+}
+
+impl<'a, A: BackingAllocator> Bump<Buffer<'a, A>> {
+    /// Construct a new arena that bump allocates into the given buffer first,
+    /// and then into new chunks allocated from `fallback` once the buffer is
+    /// full.
+    ///
+    /// See [`from_buffer`][Bump::from_buffer] for details.
+    ///
+    /// With a local array as the buffer, this is an arena whose first chunk
+    /// is on the stack, so that it doesn't touch the heap until it has
+    /// allocated more than fits in the array. The arena borrows the array, so
+    /// the arena itself can still be moved around while the chunk stays put.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{Bump, Global};
+    /// use core::mem::MaybeUninit;
+    ///
+    /// let mut buffer = [MaybeUninit::uninit(); 1024];
+    /// let range = buffer.as_ptr_range();
+    /// let bump = Bump::from_buffer_in(&mut buffer, Global);
+    ///
+    /// // Small allocations are in the buffer...
+    /// let x: *const u64 = bump.alloc(42_u64);
+    /// assert!(range.contains(&x.cast()));
+    ///
+    /// // ...and allocations that don't fit go to the global allocator.
+    /// let big: *const [u8; 1024] = bump.alloc([0_u8; 1024]);
+    /// assert!(!range.contains(&big.cast()));
+    /// ```
+    pub fn from_buffer_in(buffer: &'a mut [MaybeUninit<u8>], fallback: A) -> Self {
+        Self::from_buffer_with_policy_in(buffer, ChunkPolicy::DEFAULT, fallback)
+    }
+}
+
+impl<'a, A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool>
+    Bump<Buffer<'a, A>, MIN_ALIGN, UP>
+{
+    pub(crate) fn from_buffer_with_policy_in(
+        buffer: &'a mut [MaybeUninit<u8>],
+        chunk_policy: ChunkPolicy,
+        fallback: A,
+    ) -> Self {
+        let start = buffer.as_mut_ptr() as *mut u8;
+        let start_addr = start as usize;
+        let end_addr = start_addr + buffer.len();
+
+        // Carve a chunk out of the buffer, with its start and its footer
+        // aligned to `CHUNK_ALIGN`, like any other chunk.
+        let data_addr = round_up_to(start_addr, CHUNK_ALIGN);
+        let footer_addr = end_addr
+            .checked_sub(FOOTER_SIZE)
+            .map(|addr| round_down_to(addr, CHUNK_ALIGN));
+        let chunk = match (data_addr, footer_addr) {
+            (Some(data_addr), Some(footer_addr)) if data_addr <= footer_addr => {
+                let data = unsafe { NonNull::new_unchecked(start.add(data_addr - start_addr)) };
+                Some((data, footer_addr - data_addr))
+            }
+            _ => None,
+        };
+
+        let allocator = Buffer {
+            chunk: chunk.map(|(data, _)| data),
+            fallback,
+            buffer: PhantomData,
+        };
+        let bump = Self::try_with_capacity_and_policy_in(0, chunk_policy, allocator)
+            .unwrap_or_else(|_| unreachable!("creating an empty arena doesn't allocate"));
+
+        if let Some((data, size_without_footer)) = chunk {
+            unsafe {
+                let layout = Layout::from_size_align_unchecked(
+                    size_without_footer + FOOTER_SIZE,
+                    CHUNK_ALIGN,
+                );
+                let footer = Self::init_chunk(data, layout, size_without_footer, EMPTY_CHUNK.get());
+                bump.current_chunk_footer.set(footer);
+                bump.poison_free_space(footer.as_ref());
+            }
+        }
+
+        bump
+    }
+}
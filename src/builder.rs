@@ -0,0 +1,326 @@
+//! A builder for configuring a [`Bump`] before creating it.
+//!
+//! [`Bump`]: ../struct.Bump.html
+
+#[cfg(feature = "std")]
+use crate::AllocationBudget;
+use crate::{oom, AllocErr, BackingAllocator, Buffer, Bump, ChunkPolicy, Global};
+use core::mem::MaybeUninit;
+
+/// A builder for creating a [`Bump`] with non-default settings.
+///
+/// The builder lets you choose the allocator that the arena's chunks come
+/// from, its [minimum alignment][BumpBuilder::min_align], whether it
+/// [bumps upwards][BumpBuilder::upward], its initial capacity, its allocation
+/// limit, and how it sizes the new chunks it allocates as it grows:
+///
+/// * By default, each new chunk is twice as big as the previous one. Use
+///   [`growth_factor`][BumpBuilder::growth_factor] to change this.
+///
+/// * [`max_chunk_size`][BumpBuilder::max_chunk_size] caps how big chunks get.
+///   An allocation request that does not fit in a chunk of the maximum size
+///   gets a dedicated chunk that is just big enough for it.
+///
+/// * [`fixed_chunk_size`][BumpBuilder::fixed_chunk_size] makes every chunk the
+///   same size, again except for allocation requests that would not fit.
+///
+/// Chunk sizes refer to the number of bytes that are usable for allocations,
+/// and do not include `bumpalo`'s own per-chunk metadata. New chunks always
+/// respect the arena's [allocation limit][Bump::set_allocation_limit].
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::Bump;
+///
+/// let bump = Bump::builder()
+///     .capacity(1024)
+///     .max_chunk_size(Some(64 * 1024))
+///     .allocation_limit(Some(1024 * 1024))
+///     .build();
+///
+/// for i in 0..10_000 {
+///     bump.alloc(i);
+/// }
+/// assert!(bump.allocated_bytes() <= 1024 * 1024);
+/// ```
+///
+/// [`Bump`]: ../struct.Bump.html
+#[derive(Debug, Clone)]
+pub struct BumpBuilder<A = Global, const MIN_ALIGN: usize = 1, const UP: bool = false> {
+    allocator: A,
+    capacity: usize,
+    allocation_limit: Option<usize>,
+    #[cfg(feature = "std")]
+    allocation_budget: Option<AllocationBudget>,
+    chunk_policy: ChunkPolicy,
+    #[cfg_attr(not(feature = "debug-checks"), allow(dead_code))]
+    red_zones: bool,
+}
+
+impl BumpBuilder {
+    /// Create a new builder with the default settings, which are the same
+    /// ones that [`Bump::new`] uses.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::BumpBuilder::new().build();
+    /// # let _ = bump;
+    /// ```
+    pub fn new() -> BumpBuilder {
+        BumpBuilder {
+            allocator: Global,
+            capacity: 0,
+            allocation_limit: None,
+            #[cfg(feature = "std")]
+            allocation_budget: None,
+            chunk_policy: ChunkPolicy::DEFAULT,
+            red_zones: false,
+        }
+    }
+}
+
+impl Default for BumpBuilder {
+    fn default() -> BumpBuilder {
+        BumpBuilder::new()
+    }
+}
+
+impl<A, const MIN_ALIGN: usize, const UP: bool> BumpBuilder<A, MIN_ALIGN, UP> {
+    /// Allocate the arena's chunks from the given allocator, instead of the
+    /// global allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::builder()
+    ///     .allocator(allocator_api2::alloc::Global)
+    ///     .build();
+    /// # let _ = bump;
+    /// ```
+    pub fn allocator<B>(self, allocator: B) -> BumpBuilder<B, MIN_ALIGN, UP> {
+        BumpBuilder {
+            allocator,
+            capacity: self.capacity,
+            allocation_limit: self.allocation_limit,
+            #[cfg(feature = "std")]
+            allocation_budget: self.allocation_budget,
+            chunk_policy: self.chunk_policy,
+            red_zones: self.red_zones,
+        }
+    }
+
+    /// Create an arena that keeps all of its allocations aligned to at least
+    /// `N`. See [the `Bump` documentation](../struct.Bump.html#minimum-alignment)
+    /// for details. Defaults to 1.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::builder().min_align::<8>().build();
+    /// let x = bump.alloc(1_u8) as *mut u8;
+    /// assert_eq!(x as usize % 8, 0);
+    /// ```
+    pub fn min_align<const N: usize>(self) -> BumpBuilder<A, N, UP> {
+        BumpBuilder {
+            allocator: self.allocator,
+            capacity: self.capacity,
+            allocation_limit: self.allocation_limit,
+            #[cfg(feature = "std")]
+            allocation_budget: self.allocation_budget,
+            chunk_policy: self.chunk_policy,
+            red_zones: self.red_zones,
+        }
+    }
+
+    /// Create an arena that bumps upwards, so that the most recent allocation
+    /// can be grown in place. See
+    /// [the `Bump` documentation](../struct.Bump.html#upward-bumping) for
+    /// details. Defaults to bumping downwards.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::builder().upward().build();
+    /// # let _: bumpalo::UpBump = bump;
+    /// ```
+    pub fn upward(self) -> BumpBuilder<A, MIN_ALIGN, true> {
+        BumpBuilder {
+            allocator: self.allocator,
+            capacity: self.capacity,
+            allocation_limit: self.allocation_limit,
+            #[cfg(feature = "std")]
+            allocation_budget: self.allocation_budget,
+            chunk_policy: self.chunk_policy,
+            red_zones: self.red_zones,
+        }
+    }
+
+    /// Reserve a first chunk of at least `capacity` bytes when creating the
+    /// arena. Defaults to zero, in which case no chunk is allocated until the
+    /// first allocation.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set the arena's allocation limit in bytes. See
+    /// [`Bump::set_allocation_limit`] for details. Defaults to no limit.
+    pub fn allocation_limit(mut self, limit: Option<usize>) -> Self {
+        self.allocation_limit = limit;
+        self
+    }
+
+    /// Set the budget, shared with other arenas, that the arena draws its
+    /// chunks from. See [`Bump::set_allocation_budget`] for details. Defaults
+    /// to no budget.
+    ///
+    /// This method is only available with the `std` cargo feature.
+    #[cfg(feature = "std")]
+    pub fn allocation_budget(mut self, budget: Option<AllocationBudget>) -> Self {
+        self.allocation_budget = budget;
+        self
+    }
+
+    /// Make each new chunk `factor` times as big as the previous one. Defaults
+    /// to 2. A factor of 1 keeps all chunks the same size as the arena's
+    /// first chunk.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `factor` is zero.
+    pub fn growth_factor(mut self, factor: usize) -> Self {
+        assert!(factor > 0, "chunk growth factor must be greater than zero");
+        self.chunk_policy.growth_factor = factor;
+        self
+    }
+
+    /// Never allocate chunks bigger than `size` bytes, except when a single
+    /// allocation request does not fit in a chunk of that size. Such a
+    /// request gets a chunk of its own that is just big enough for it.
+    /// Defaults to no maximum.
+    pub fn max_chunk_size(mut self, size: Option<usize>) -> Self {
+        self.chunk_policy.max_chunk_size = size;
+        self
+    }
+
+    /// Allocate every chunk with exactly `size` usable bytes (rounded up to
+    /// `bumpalo`'s internal chunk alignment), except when a single allocation
+    /// request does not fit in a chunk of that size. Such a request gets a
+    /// chunk of its own that is just big enough for it.
+    ///
+    /// When set, this overrides the growth factor and the maximum chunk size.
+    /// Defaults to not using fixed-size chunks.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::builder()
+    ///     .fixed_chunk_size(Some(4096))
+    ///     .build();
+    ///
+    /// bump.alloc(0_u8);
+    /// assert_eq!(bump.allocated_bytes(), 4096);
+    ///
+    /// bump.alloc([0_u8; 3000]);
+    /// bump.alloc([0_u8; 3000]);
+    /// assert_eq!(bump.allocated_bytes(), 2 * 4096);
+    /// ```
+    pub fn fixed_chunk_size(mut self, size: Option<usize>) -> Self {
+        self.chunk_policy.fixed_chunk_size = size;
+        self
+    }
+
+    /// Put red zones next to the arena's allocations when the `debug-checks`
+    /// cargo feature is enabled, so that
+    /// [`Bump::check_integrity`](../struct.Bump.html#method.check_integrity)
+    /// can catch writes past their end. Defaults to false.
+    ///
+    /// The red zones take up room in the arena's chunks, so allocations are
+    /// no longer back to back. Without the feature, this does nothing.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::builder().red_zones(true).build();
+    /// # let _ = bump;
+    /// ```
+    pub fn red_zones(mut self, enabled: bool) -> Self {
+        self.red_zones = enabled;
+        self
+    }
+}
+
+impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> BumpBuilder<A, MIN_ALIGN, UP> {
+    /// Create the configured arena.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if allocating the initial chunk fails.
+    pub fn build(self) -> Bump<A, MIN_ALIGN, UP> {
+        self.try_build().unwrap_or_else(|_| oom())
+    }
+
+    /// Attempt to create the configured arena.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if allocating the initial chunk fails.
+    pub fn try_build(self) -> Result<Bump<A, MIN_ALIGN, UP>, AllocErr> {
+        #[allow(unused_mut)]
+        let mut bump = Bump::try_with_capacity_and_policy_in(
+            self.capacity,
+            self.chunk_policy,
+            self.allocator,
+        )?;
+        #[cfg(feature = "debug-checks")]
+        if self.red_zones {
+            bump.red_zones.enable();
+        }
+        bump.set_allocation_limit(self.allocation_limit);
+        #[cfg(feature = "std")]
+        bump.set_allocation_budget(self.allocation_budget);
+        Ok(bump)
+    }
+
+    /// Create the configured arena over the given buffer, which becomes its
+    /// first chunk. The configured allocator is only used for new chunks once
+    /// the buffer is full; use [`NoAlloc`][crate::NoAlloc] to make
+    /// allocations fail instead. The configured capacity is ignored.
+    ///
+    /// See [`Bump::from_buffer`](../struct.Bump.html#method.from_buffer) for
+    /// details.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{Bump, NoAlloc};
+    /// use core::mem::MaybeUninit;
+    ///
+    /// let mut buffer = [MaybeUninit::uninit(); 1024];
+    /// let bump = Bump::builder()
+    ///     .allocator(NoAlloc)
+    ///     .upward()
+    ///     .build_with_buffer(&mut buffer);
+    ///
+    /// let a = bump.alloc(1_u8) as *mut u8;
+    /// let b = bump.alloc(2_u8) as *mut u8;
+    /// assert!(b > a);
+    /// ```
+    pub fn build_with_buffer<'a>(
+        self,
+        buffer: &'a mut [MaybeUninit<u8>],
+    ) -> Bump<Buffer<'a, A>, MIN_ALIGN, UP> {
+        #[allow(unused_mut)]
+        let mut bump = Bump::from_buffer_with_policy_in(buffer, self.chunk_policy, self.allocator);
+        #[cfg(feature = "debug-checks")]
+        if self.red_zones {
+            bump.red_zones.enable();
+        }
+        bump.set_allocation_limit(self.allocation_limit);
+        #[cfg(feature = "std")]
+        bump.set_allocation_budget(self.allocation_budget);
+        bump
+    }
+}
@@ -0,0 +1,58 @@
+//! The error for C strings that would have a nul byte in them.
+//!
+//! See [`NulError`] for details.
+//!
+//! [`NulError`]: ../struct.NulError.html
+
+use core::fmt;
+
+/// An error indicating that bytes meant for a C string contain a nul byte
+/// before their end.
+///
+/// This is returned by [`Bump::alloc_str_as_c_str`] and by the methods of
+/// `collections::CString` that add bytes to it. Unlike
+/// [`std::ffi::NulError`], it doesn't hold on to a copy of the bytes, so
+/// creating it never allocates.
+///
+/// [`Bump::alloc_str_as_c_str`]: ./struct.Bump.html#method.alloc_str_as_c_str
+/// [`std::ffi::NulError`]: https://doc.rust-lang.org/std/ffi/struct.NulError.html
+///
+/// ## Example
+///
+/// ```
+/// let bump = bumpalo::Bump::new();
+/// let err = bump.alloc_str_as_c_str("nul\0byte").unwrap_err();
+/// assert_eq!(err.nul_position(), 3);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NulError {
+    position: usize,
+}
+
+impl NulError {
+    /// Find the first nul byte in `bytes`, if there is one.
+    pub(crate) fn check(bytes: &[u8]) -> Result<(), NulError> {
+        match bytes.iter().position(|&b| b == 0) {
+            Some(position) => Err(NulError { position }),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the position of the nul byte in the bytes that were given.
+    pub fn nul_position(&self) -> usize {
+        self.position
+    }
+}
+
+impl fmt::Display for NulError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "nul byte found in provided data at position: {}",
+            self.position
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NulError {}
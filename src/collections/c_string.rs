@@ -0,0 +1,427 @@
+//! A growable, nul-terminated C string.
+//!
+//! This module contains the [`CString`] type, which is like
+//! [`std::ffi::CString`], but is allocated in a bump arena and can grow.
+//!
+//! [`CString`]: struct.CString.html
+//! [`std::ffi::CString`]: https://doc.rust-lang.org/std/ffi/struct.CString.html
+//!
+//! # Examples
+//!
+//! ```
+//! use bumpalo::{Bump, collections::CString};
+//!
+//! let b = Bump::new();
+//!
+//! let mut s = CString::new_in(&b);
+//! s.push_str("hello").unwrap();
+//! s.push_bytes(b", world").unwrap();
+//! assert_eq!(s.as_bytes_with_nul(), b"hello, world\0");
+//!
+//! // Nul bytes can't be pushed.
+//! assert!(s.push_str("\0").is_err());
+//! ```
+
+use crate::collections::vec::Vec;
+use crate::collections::CollectionAllocErr;
+use crate::{Bump, NulError};
+use core::borrow::Borrow;
+use core::ffi::CStr;
+use core::fmt;
+use core::num::NonZeroU8;
+use core::ops::Deref;
+
+/// A growable C string, allocated in a bump arena.
+///
+/// A `CString` always ends with a nul terminator and has no other nul bytes,
+/// so it can be handed to C code with [`as_ptr`] at any time. It dereferences
+/// to a [`CStr`].
+///
+/// [`as_ptr`]: https://doc.rust-lang.org/std/ffi/struct.CStr.html#method.as_ptr
+/// [`CStr`]: https://doc.rust-lang.org/std/ffi/struct.CStr.html
+///
+/// # Examples
+///
+/// ```
+/// use bumpalo::{Bump, collections::CString};
+/// use std::ffi::CStr;
+///
+/// let b = Bump::new();
+///
+/// let mut path = CString::from_bytes_in(b"/usr", &b).unwrap();
+/// path.push_str("/lib").unwrap();
+///
+/// let path: &CStr = path.into_bump_c_str();
+/// assert_eq!(path.to_str(), Ok("/usr/lib"));
+/// ```
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CString<'bump> {
+    // The bytes, always followed by exactly one nul byte.
+    vec: Vec<'bump, u8>,
+}
+
+impl<'bump> CString<'bump> {
+    /// Creates a new empty `CString`, which only holds its nul terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let s = CString::new_in(&b);
+    /// assert!(s.is_empty());
+    /// assert_eq!(s.as_bytes_with_nul(), b"\0");
+    /// ```
+    #[inline]
+    pub fn new_in(bump: &'bump Bump) -> CString<'bump> {
+        CString::with_capacity_in(0, bump)
+    }
+
+    /// Creates a new empty `CString` with room for at least `capacity` bytes,
+    /// not counting the nul terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let s = CString::with_capacity_in(10, &b);
+    /// assert!(s.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, bump: &'bump Bump) -> CString<'bump> {
+        let mut vec = Vec::with_capacity_in(capacity.saturating_add(1), bump);
+        vec.push(0);
+        CString { vec }
+    }
+
+    /// Creates a new `CString` from the given bytes, which must not contain a
+    /// nul byte. The nul terminator is added after them.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `bytes` contains a nul byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let s = CString::from_bytes_in(b"foo", &b).unwrap();
+    /// assert_eq!(s.as_bytes_with_nul(), b"foo\0");
+    ///
+    /// let err = CString::from_bytes_in(b"f\0o", &b).unwrap_err();
+    /// assert_eq!(err.nul_position(), 1);
+    /// ```
+    pub fn from_bytes_in(bytes: &[u8], bump: &'bump Bump) -> Result<CString<'bump>, NulError> {
+        NulError::check(bytes)?;
+        let mut vec = Vec::with_capacity_in(bytes.len() + 1, bump);
+        vec.extend_from_slice(bytes);
+        vec.push(0);
+        Ok(CString { vec })
+    }
+
+    /// Creates a new `CString` with a copy of the given C string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    /// use std::ffi::CStr;
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let c_str = CStr::from_bytes_with_nul(b"foo\0").unwrap();
+    /// let s = CString::from_c_str_in(c_str, &b);
+    /// assert_eq!(s.as_c_str(), c_str);
+    /// ```
+    pub fn from_c_str_in(s: &CStr, bump: &'bump Bump) -> CString<'bump> {
+        let bytes = s.to_bytes_with_nul();
+        let mut vec = Vec::with_capacity_in(bytes.len(), bump);
+        vec.extend_from_slice(bytes);
+        CString { vec }
+    }
+
+    /// Returns the arena that this `CString` is allocated in.
+    #[inline]
+    pub fn bump(&self) -> &'bump Bump {
+        self.vec.bump()
+    }
+
+    /// Returns the length of this `CString` in bytes, not counting the nul
+    /// terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let s = CString::from_bytes_in(b"foo", &b).unwrap();
+    /// assert_eq!(s.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len() - 1
+    }
+
+    /// Returns `true` if this `CString` has a length of zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of bytes this `CString` can hold without
+    /// reallocating, not counting the nul terminator.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity() - 1
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more bytes, like
+    /// [`reserve`] but returning an error instead of panicking if allocating
+    /// fails.
+    ///
+    /// [`reserve`]: #method.reserve
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.vec.try_reserve(additional)
+    }
+
+    /// Appends a byte to the end of this `CString`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    /// use std::num::NonZeroU8;
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let mut s = CString::new_in(&b);
+    /// s.push(NonZeroU8::new(b'a').unwrap());
+    /// assert_eq!(s.as_bytes_with_nul(), b"a\0");
+    /// ```
+    #[inline]
+    pub fn push(&mut self, byte: NonZeroU8) {
+        self.vec.reserve(1);
+        let len = self.len();
+        self.vec[len] = byte.get();
+        self.vec.push(0);
+    }
+
+    /// Appends the given bytes to the end of this `CString`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `bytes` contains a nul byte, in which case nothing is
+    /// appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let mut s = CString::new_in(&b);
+    /// s.push_bytes(b"foo").unwrap();
+    /// assert!(s.push_bytes(b"bar\0baz").is_err());
+    /// assert_eq!(s.as_bytes(), b"foo");
+    /// ```
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<(), NulError> {
+        NulError::check(bytes)?;
+        self.vec.reserve(bytes.len());
+        self.vec.pop();
+        self.vec.extend_from_slice(bytes);
+        self.vec.push(0);
+        Ok(())
+    }
+
+    /// Appends the given string slice to the end of this `CString`.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `s` contains a nul byte, in which case nothing is appended.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let mut s = CString::new_in(&b);
+    /// s.push_str("foo").unwrap();
+    /// assert_eq!(s.to_str(), Ok("foo"));
+    /// ```
+    #[inline]
+    pub fn push_str(&mut self, s: &str) -> Result<(), NulError> {
+        self.push_bytes(s.as_bytes())
+    }
+
+    /// Removes the last byte from this `CString` and returns it, or `None` if
+    /// it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let mut s = CString::from_bytes_in(b"ab", &b).unwrap();
+    /// assert_eq!(s.pop().map(|b| b.get()), Some(b'b'));
+    /// assert_eq!(s.as_bytes_with_nul(), b"a\0");
+    /// ```
+    pub fn pop(&mut self) -> Option<NonZeroU8> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        let byte = self.vec[len - 1];
+        self.vec.truncate(len);
+        self.vec[len - 1] = 0;
+        NonZeroU8::new(byte)
+    }
+
+    /// Shortens this `CString` to `len` bytes. Does nothing if it is already
+    /// no longer than that.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.vec.truncate(len + 1);
+            self.vec[len] = 0;
+        }
+    }
+
+    /// Removes all bytes from this `CString`, except for the nul terminator.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Returns the contents of this `CString` as a `CStr`.
+    #[inline]
+    pub fn as_c_str(&self) -> &CStr {
+        unsafe {
+            // This is OK, because we only ever keep a single nul byte at the
+            // end.
+            CStr::from_bytes_with_nul_unchecked(&self.vec)
+        }
+    }
+
+    /// Returns the bytes of this `CString`, without the nul terminator.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec[..self.len()]
+    }
+
+    /// Returns the bytes of this `CString`, including the nul terminator.
+    #[inline]
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// Converts this `CString` into its bytes, without the nul terminator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let s = CString::from_bytes_in(b"foo", &b).unwrap();
+    /// assert_eq!(s.into_bytes(), b"foo");
+    /// ```
+    pub fn into_bytes(self) -> Vec<'bump, u8> {
+        let mut vec = self.vec;
+        vec.pop();
+        vec
+    }
+
+    /// Converts this `CString` into a `&'bump CStr`. This is analogous to
+    /// [`std::ffi::CString::into_boxed_c_str`][into_boxed_c_str].
+    ///
+    /// [into_boxed_c_str]: https://doc.rust-lang.org/std/ffi/struct.CString.html#method.into_boxed_c_str
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::CString};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let s = CString::from_bytes_in(b"foo", &b).unwrap();
+    /// assert_eq!(s.into_bump_c_str().to_bytes(), b"foo");
+    /// ```
+    pub fn into_bump_c_str(self) -> &'bump CStr {
+        let bytes = self.vec.into_bump_slice();
+        unsafe {
+            // This is OK, because we only ever keep a single nul byte at the
+            // end.
+            CStr::from_bytes_with_nul_unchecked(bytes)
+        }
+    }
+}
+
+impl<'bump> Deref for CString<'bump> {
+    type Target = CStr;
+
+    #[inline]
+    fn deref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl<'bump> AsRef<CStr> for CString<'bump> {
+    #[inline]
+    fn as_ref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl<'bump> Borrow<CStr> for CString<'bump> {
+    #[inline]
+    fn borrow(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl<'bump> PartialEq<CStr> for CString<'bump> {
+    #[inline]
+    fn eq(&self, other: &CStr) -> bool {
+        self.as_c_str() == other
+    }
+}
+
+impl<'a, 'bump> PartialEq<&'a CStr> for CString<'bump> {
+    #[inline]
+    fn eq(&self, other: &&'a CStr) -> bool {
+        self.as_c_str() == *other
+    }
+}
+
+impl<'bump> fmt::Debug for CString<'bump> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_c_str(), f)
+    }
+}
@@ -23,6 +23,9 @@ mod str;
 pub mod string;
 pub use self::string::String;
 
+pub mod c_string;
+pub use self::c_string::CString;
+
 mod collect_in;
 pub use collect_in::{CollectIn, FromIteratorIn};
 
@@ -65,15 +68,16 @@ pub enum CollectionAllocErr {
     /// Error due to the computed capacity exceeding the collection's maximum
     /// (usually `isize::MAX` bytes).
     CapacityOverflow,
-    /// Error due to the allocator (see the documentation for the [`AllocErr`] type).
-    AllocErr,
+    /// Error due to the allocator (see the documentation for the [`AllocErr`]
+    /// type, which tells whether it was the arena's allocation limit).
+    AllocErr(AllocErr),
 }
 
 // #[unstable(feature = "try_reserve", reason = "new API", issue="48043")]
 impl From<AllocErr> for CollectionAllocErr {
     #[inline]
-    fn from(AllocErr: AllocErr) -> Self {
-        CollectionAllocErr::AllocErr
+    fn from(err: AllocErr) -> Self {
+        CollectionAllocErr::AllocErr(err)
     }
 }
 
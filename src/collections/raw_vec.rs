@@ -104,7 +104,13 @@ impl<'a, T> RawVec<'a, T> {
                 };
                 match result {
                     Ok(ptr) => ptr.cast(),
-                    Err(_) => handle_alloc_error(layout),
+                    Err(_) => {
+                        let ptr = a.handle_oom(layout);
+                        if zeroed {
+                            ptr::write_bytes(ptr.as_ptr(), 0, alloc_size);
+                        }
+                        ptr.cast()
+                    }
                 }
             };
 
@@ -245,10 +251,11 @@ impl<'a, T> RawVec<'a, T> {
                     let ptr_res = self.a.realloc(self.ptr.cast(), cur, new_size);
                     match ptr_res {
                         Ok(ptr) => (new_cap, ptr.cast()),
-                        Err(_) => handle_alloc_error(Layout::from_size_align_unchecked(
-                            new_size,
-                            cur.align(),
-                        )),
+                        Err(_) => {
+                            let new_layout =
+                                Layout::from_size_align_unchecked(new_size, cur.align());
+                            (new_cap, self.realloc_after_oom(cur, new_layout))
+                        }
                     }
                 }
                 None => {
@@ -257,7 +264,10 @@ impl<'a, T> RawVec<'a, T> {
                     let new_cap = if elem_size > (!0) / 8 { 1 } else { 4 };
                     match self.a.alloc_array::<T>(new_cap) {
                         Ok(ptr) => (new_cap, ptr),
-                        Err(_) => handle_alloc_error(Layout::array::<T>(new_cap).unwrap()),
+                        Err(_) => {
+                            let layout = Layout::array::<T>(new_cap).unwrap();
+                            (new_cap, self.a.handle_oom(layout).cast())
+                        }
                     }
                 }
             };
@@ -631,7 +641,7 @@ impl<'a, T> RawVec<'a, T> {
         // call site.
         match self.reserve_internal(used_cap, needed_extra_cap, Infallible, strategy) {
             Err(CapacityOverflow) => capacity_overflow(),
-            Err(AllocErr) => unreachable!(),
+            Err(AllocErr(_)) => unreachable!(),
             Ok(()) => { /* yay */ }
         }
     }
@@ -639,14 +649,29 @@ impl<'a, T> RawVec<'a, T> {
     #[inline(never)]
     fn reserve_internal_or_error(
         &mut self,
-         used_cap: usize,
-         needed_extra_cap: usize,
-         fallibility: Fallibility,
-         strategy: ReserveStrategy,)-> Result<(), CollectionAllocErr> {
+        used_cap: usize,
+        needed_extra_cap: usize,
+        fallibility: Fallibility,
+        strategy: ReserveStrategy,
+    ) -> Result<(), CollectionAllocErr> {
         // Delegates the call to `reserve_internal`, which can be inlined.
         self.reserve_internal(used_cap, needed_extra_cap, fallibility, strategy)
     }
 
+    /// Let the arena's out-of-memory handler deal with failing to grow the
+    /// buffer from `old_layout` to `new_layout`, and copy the buffer into the
+    /// allocation that it ends up with.
+    #[cold]
+    unsafe fn realloc_after_oom(&mut self, old_layout: Layout, new_layout: Layout) -> NonNull<T> {
+        let ptr = self.a.handle_oom(new_layout);
+        ptr::copy_nonoverlapping(
+            self.ptr.as_ptr() as *const u8,
+            ptr.as_ptr(),
+            old_layout.size(),
+        );
+        ptr.cast()
+    }
+
     /// Helper method to reserve additional space, reallocating the backing memory.
     /// The caller is responsible for confirming that there is not already enough space available.
     fn reserve_internal(
@@ -657,8 +682,6 @@ impl<'a, T> RawVec<'a, T> {
         strategy: ReserveStrategy,
     ) -> Result<(), CollectionAllocErr> {
         unsafe {
-            use crate::AllocErr;
-
             // NOTE: we don't early branch on ZSTs here because we want this
             // to actually catch "asking for more than usize::MAX" in that case.
             // If we make it past the first branch then we are guaranteed to
@@ -683,11 +706,13 @@ impl<'a, T> RawVec<'a, T> {
                 None => Alloc::alloc(&mut self.a, new_layout),
             };
 
-            if let (Err(AllocErr), Infallible) = (&res, fallibility) {
-                handle_alloc_error(new_layout);
-            }
-
-            self.ptr = res?.cast();
+            self.ptr = match (res, fallibility) {
+                (Err(_), Infallible) => match self.current_layout() {
+                    Some(layout) => self.realloc_after_oom(layout, new_layout),
+                    None => self.a.handle_oom(new_layout).cast(),
+                },
+                (res, _) => res?.cast(),
+            };
             self.cap = new_cap;
 
             Ok(())
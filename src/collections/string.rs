@@ -54,6 +54,7 @@
 
 use crate::collections::str::lossy;
 use crate::collections::vec::Vec;
+use crate::collections::CollectionAllocErr;
 use crate::Bump;
 use core::borrow::{Borrow, BorrowMut};
 use core::char::decode_utf16;
@@ -81,21 +82,86 @@ use core_alloc::borrow::Cow;
 ///
 /// let who = "World";
 /// let s = bumpalo::format!(in &b, "Hello, {}!", who);
-/// assert_eq!(s, "Hello, World!")
+/// assert_eq!(s, "Hello, World!");
+///
+/// let s = bumpalo::format!(in &b, "Hello, {who}! {n:>3}", n = 1);
+/// assert_eq!(s, "Hello, World!   1");
 /// ```
 #[macro_export]
 macro_rules! format {
-    ( in $bump:expr, $fmt:expr, $($args:expr),* ) => {{
-        use $crate::core_alloc::fmt::Write;
-        let bump = $bump;
-        let mut s = $crate::collections::String::new_in(bump);
-        let _ = write!(&mut s, $fmt, $($args),*);
-        s
-    }};
+    ( in $bump:expr, $($arg:tt)* ) => {
+        $crate::collections::string::format_in($bump, ::core::format_args!($($arg)*))
+    };
+}
 
-    ( in $bump:expr, $fmt:expr, $($args:expr,)* ) => {
-        $crate::format!(in $bump, $fmt, $($args),*)
+/// Like the [`format!`](macro.format.html) macro, but returns an error instead
+/// of panicking if the arena fails to allocate the string.
+///
+/// # Examples
+///
+/// ```
+/// use bumpalo::Bump;
+///
+/// let b = Bump::new();
+///
+/// let who = "World";
+/// let s = bumpalo::try_format!(in &b, "Hello, {who}!").unwrap();
+/// assert_eq!(s, "Hello, World!");
+///
+/// b.set_allocation_limit(Some(b.allocated_bytes()));
+/// let big = "x".repeat(100_000);
+/// assert!(bumpalo::try_format!(in &b, "{big}").is_err());
+/// ```
+#[macro_export]
+macro_rules! try_format {
+    ( in $bump:expr, $($arg:tt)* ) => {
+        $crate::collections::string::try_format_in($bump, ::core::format_args!($($arg)*))
+    };
+}
+
+// The implementation of `format!`.
+#[doc(hidden)]
+pub fn format_in<'bump>(bump: &'bump Bump, args: fmt::Arguments<'_>) -> String<'bump> {
+    let mut s = String::new_in(bump);
+    fmt::Write::write_fmt(&mut s, args)
+        .expect("a formatting trait implementation returned an error");
+    s
+}
+
+// The implementation of `try_format!`.
+#[doc(hidden)]
+pub fn try_format_in<'bump>(
+    bump: &'bump Bump,
+    args: fmt::Arguments<'_>,
+) -> Result<String<'bump>, CollectionAllocErr> {
+    // Writes into a `String`, remembering why it couldn't grow.
+    struct Writer<'a, 'bump> {
+        s: &'a mut String<'bump>,
+        error: Option<CollectionAllocErr>,
+    }
+
+    impl fmt::Write for Writer<'_, '_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if let Err(e) = self.s.try_reserve(s.len()) {
+                self.error = Some(e);
+                return Err(fmt::Error);
+            }
+            self.s.push_str(s);
+            Ok(())
+        }
+    }
+
+    let mut s = String::new_in(bump);
+    let mut writer = Writer {
+        s: &mut s,
+        error: None,
     };
+    if fmt::Write::write_fmt(&mut writer, args).is_err() {
+        return Err(writer
+            .error
+            .expect("a formatting trait implementation returned an error"));
+    }
+    Ok(s)
 }
 
 /// A UTF-8 encoded, growable string.
@@ -1071,6 +1137,50 @@ impl<'bump> String<'bump> {
         self.vec.reserve_exact(additional)
     }
 
+    /// Tries to reserve capacity for at least `additional` more bytes to be
+    /// inserted in this `String`, like [`reserve`] but returning an error
+    /// instead of panicking if allocating fails.
+    ///
+    /// [`reserve`]: #method.reserve
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::String};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let mut s = String::new_in(&b);
+    /// s.try_reserve(10).unwrap();
+    /// assert!(s.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.vec.try_reserve(additional)
+    }
+
+    /// Tries to ensure that this `String`'s capacity is `additional` bytes
+    /// larger than its length, like [`reserve_exact`] but returning an error
+    /// instead of panicking if allocating fails.
+    ///
+    /// [`reserve_exact`]: #method.reserve_exact
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bumpalo::{Bump, collections::String};
+    ///
+    /// let b = Bump::new();
+    ///
+    /// let mut s = String::new_in(&b);
+    /// s.try_reserve_exact(10).unwrap();
+    /// assert!(s.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), CollectionAllocErr> {
+        self.vec.try_reserve_exact(additional)
+    }
+
     /// Shrinks the capacity of this `String` to match its length.
     ///
     /// # Examples
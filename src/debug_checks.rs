@@ -0,0 +1,220 @@
+//! Red zones and fill patterns that catch memory errors in a `Bump`, used
+//! only when the `debug-checks` cargo feature is enabled.
+//!
+//! See [`Bump::check_integrity`] for details.
+//!
+//! [`Bump::check_integrity`]: ../struct.Bump.html#method.check_integrity
+
+#[cfg(feature = "debug-checks")]
+use core::cell::RefCell;
+#[cfg(feature = "debug-checks")]
+use core::fmt;
+#[cfg(feature = "debug-checks")]
+use core::slice;
+#[cfg(feature = "debug-checks")]
+use core_alloc::alloc::Layout;
+#[cfg(feature = "debug-checks")]
+use core_alloc::vec::Vec;
+
+/// The byte that fresh allocations are filled with.
+#[cfg(feature = "debug-checks")]
+pub(crate) const FRESH_BYTE: u8 = 0xCD;
+
+/// The byte that memory is filled with when it is deallocated or reset.
+#[cfg(feature = "debug-checks")]
+pub(crate) const FREED_BYTE: u8 = 0xDD;
+
+/// The byte that red zones are filled with.
+#[cfg(feature = "debug-checks")]
+pub(crate) const CANARY_BYTE: u8 = 0xFD;
+
+/// The size of the red zone that comes with each allocation. This is a
+/// multiple of every supported `MIN_ALIGN`.
+#[cfg(feature = "debug-checks")]
+pub(crate) const RED_ZONE_SIZE: usize = 16;
+
+/// The layout to bump allocate for `layout` and its red zone, along with the
+/// offset of the allocation within it.
+///
+/// Downward-bumping arenas write past the end of an allocation into memory
+/// that was allocated before it, so the red zone goes after the allocation.
+/// Upward-bumping arenas write past the end of an allocation into the next
+/// one, so each allocation's red zone goes before it, where it guards the
+/// previous allocation. Either way, the allocation itself ends up next to the
+/// bump finger and can still be grown, shrunk and deallocated in place.
+#[cfg(feature = "debug-checks")]
+pub(crate) fn guarded_layout(
+    layout: Layout,
+    min_align: usize,
+    upward: bool,
+) -> Option<(Layout, usize)> {
+    if upward {
+        let offset = crate::round_up_to(RED_ZONE_SIZE, layout.align().max(min_align))?;
+        let size = layout.size().checked_add(offset)?;
+        Some((Layout::from_size_align(size, layout.align()).ok()?, offset))
+    } else {
+        let size = crate::round_up_to(layout.size(), min_align)?.checked_add(RED_ZONE_SIZE)?;
+        Some((Layout::from_size_align(size, layout.align()).ok()?, 0))
+    }
+}
+
+/// A red zone, and the allocation that it was made for.
+#[cfg(feature = "debug-checks")]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RedZone {
+    // The address of the footer of the chunk that the red zone is in.
+    pub(crate) chunk: usize,
+    pub(crate) start: usize,
+    pub(crate) len: usize,
+    pub(crate) allocation: usize,
+    pub(crate) layout: Layout,
+}
+
+// The red zones of a `Bump`'s live allocations, oldest first. Without the
+// `debug-checks` feature, this is empty.
+//
+// This is kept in the global heap rather than in the arena, so that
+// overwriting the arena's memory can't corrupt it.
+#[derive(Debug, Default)]
+pub(crate) struct RedZones {
+    #[cfg(feature = "debug-checks")]
+    zones: RefCell<Vec<RedZone>>,
+    // Red zones change where allocations land, so arenas only get them when
+    // they are built with `BumpBuilder::red_zones(true)`.
+    #[cfg(feature = "debug-checks")]
+    enabled: bool,
+}
+
+#[cfg(feature = "debug-checks")]
+impl RedZones {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    /// Remember a new red zone. If the global allocator can't make room for
+    /// it, the red zone is not checked, rather than failing the allocation.
+    pub(crate) fn push(&self, zone: RedZone) {
+        let mut zones = self.zones.borrow_mut();
+        if zones.try_reserve(1).is_ok() {
+            zones.push(zone);
+        }
+    }
+
+    /// Forget the newest red zone if it belongs to the allocation at
+    /// `allocation`, because that allocation was deallocated.
+    pub(crate) fn forget_newest(&self, allocation: usize) {
+        let mut zones = self.zones.borrow_mut();
+        if zones.last().is_some_and(|z| z.allocation == allocation) {
+            zones.pop();
+        }
+    }
+
+    /// Update the newest red zone's allocation after it was moved from
+    /// `old_allocation` or resized in place.
+    pub(crate) fn update_newest(&self, old_allocation: usize, allocation: usize, layout: Layout) {
+        if let Some(zone) = self.zones.borrow_mut().last_mut() {
+            if zone.allocation == old_allocation {
+                zone.allocation = allocation;
+                zone.layout = layout;
+            }
+        }
+    }
+
+    /// Forget the newest red zones for as long as `is_live` says that their
+    /// memory is no longer in use.
+    pub(crate) fn forget_dead(&self, mut is_live: impl FnMut(&RedZone) -> bool) {
+        let mut zones = self.zones.borrow_mut();
+        while zones.last().is_some_and(|z| !is_live(z)) {
+            zones.pop();
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.zones.borrow_mut().clear();
+    }
+
+    /// Check that every red zone still holds its canary bytes.
+    ///
+    /// ## Safety
+    ///
+    /// All red zones must be in memory that is still allocated.
+    pub(crate) unsafe fn check(&self, upward: bool) -> Result<(), IntegrityError> {
+        let zones = self.zones.borrow();
+        for (i, zone) in zones.iter().enumerate() {
+            let bytes = slice::from_raw_parts(zone.start as *const u8, zone.len);
+            let offset = match bytes.iter().position(|&b| b != CANARY_BYTE) {
+                Some(offset) => offset,
+                None => continue,
+            };
+
+            // In upward-bumping arenas, the red zone is right after the
+            // previous allocation in the same chunk, if there is one.
+            let culprit = if upward && i > 0 && zones[i - 1].chunk == zone.chunk {
+                &zones[i - 1]
+            } else {
+                zone
+            };
+            return Err(IntegrityError {
+                allocation: culprit.allocation,
+                layout: culprit.layout,
+                corrupted: zone.start + offset,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A red zone next to an allocation in a [`Bump`] that was overwritten, as
+/// found by [`Bump::check_integrity`].
+///
+/// This type is only available with the `debug-checks` cargo feature.
+///
+/// [`Bump`]: struct.Bump.html
+/// [`Bump::check_integrity`]: struct.Bump.html#method.check_integrity
+#[cfg(feature = "debug-checks")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityError {
+    allocation: usize,
+    layout: Layout,
+    corrupted: usize,
+}
+
+#[cfg(feature = "debug-checks")]
+impl IntegrityError {
+    /// Get the start of the allocation next to the overwritten red zone,
+    /// which is most likely the one that was written out of bounds.
+    pub fn allocation(&self) -> *const u8 {
+        self.allocation as *const u8
+    }
+
+    /// Get the layout that the allocation was made, or last resized, with.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Get the address of the first overwritten byte of the red zone.
+    pub fn corrupted(&self) -> *const u8 {
+        self.corrupted as *const u8
+    }
+}
+
+#[cfg(feature = "debug-checks")]
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "red zone overwritten at {:#x}, at offset {} of the {}-byte allocation at {:#x}",
+            self.corrupted,
+            self.corrupted.wrapping_sub(self.allocation) as isize,
+            self.layout.size(),
+            self.allocation
+        )
+    }
+}
+
+#[cfg(all(feature = "debug-checks", feature = "std"))]
+impl std::error::Error for IntegrityError {}
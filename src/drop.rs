@@ -0,0 +1,363 @@
+//! An arena that runs the destructors of the values allocated in it.
+//!
+//! See [`DropBump`] for details.
+//!
+//! [`DropBump`]: ../struct.DropBump.html
+
+use crate::{BackingAllocator, Bump, Global};
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::Deref;
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// A link in a `DropBump`'s list of values that need to be dropped. It is
+/// allocated in the arena, right before the value that it drops.
+#[repr(C)]
+struct DropEntry {
+    // Drops the value that this entry is the header of.
+    drop: unsafe fn(NonNull<DropEntry>),
+    // The entry of the previous value that needs to be dropped.
+    prev: Option<NonNull<DropEntry>>,
+}
+
+/// A value allocated in a `DropBump`, together with its entry in the drop
+/// list.
+#[repr(C)]
+struct Tracked<T> {
+    entry: DropEntry,
+    value: T,
+}
+
+unsafe fn drop_tracked<T>(entry: NonNull<DropEntry>) {
+    let tracked = entry.cast::<Tracked<T>>().as_ptr();
+    ptr::drop_in_place(ptr::addr_of_mut!((*tracked).value));
+}
+
+/// A slice allocated in a `DropBump`. Unlike single values, the entry is
+/// allocated after the slice, once all of its elements are initialized.
+#[repr(C)]
+struct TrackedSlice<T> {
+    entry: DropEntry,
+    ptr: NonNull<T>,
+    len: usize,
+}
+
+unsafe fn drop_tracked_slice<T>(entry: NonNull<DropEntry>) {
+    let tracked = entry.cast::<TrackedSlice<T>>().as_ref();
+    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+        tracked.ptr.as_ptr(),
+        tracked.len,
+    ));
+}
+
+/// An arena that runs the `Drop` implementations of the values allocated in
+/// it when it is reset or dropped.
+///
+/// A [`Bump`] never runs any destructors, so values that own resources, such
+/// as a `std::vec::Vec`, a file, or an `Rc`, leak those resources when they are
+/// allocated in it. A `DropBump` keeps track of every value allocated through
+/// its own allocation methods whose type [needs to be
+/// dropped][core::mem::needs_drop], in a list that is itself allocated in the
+/// arena, and drops them all in the reverse order of their allocation when
+/// [`reset`][DropBump::reset] or dropped. Values of types that don't need to be
+/// dropped are not tracked, and cost nothing extra.
+///
+/// The values allocated in a `DropBump` may borrow data that outlives the
+/// arena, described by the `'a` lifetime, but not data that the arena
+/// outlives, since their destructors may use it:
+///
+/// ```compile_fail
+/// let bump = bumpalo::DropBump::new();
+/// let s = String::from("dropped before the arena");
+/// bump.alloc(vec![&s]);
+/// ```
+///
+/// A `DropBump` dereferences to its underlying [`Bump`], so all of `Bump`'s
+/// allocation methods are available too, but values allocated through them
+/// are not dropped. Rolling the underlying arena back with
+/// [`reset_to`][Bump::reset_to] to before a value that will be dropped is
+/// undefined behavior. The `DropBump` is not `Send`, since it may drop values
+/// that are not `Send` either.
+///
+/// If a destructor panics, the values that were allocated before it are
+/// leaked instead of dropped.
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::DropBump;
+/// use std::rc::Rc;
+///
+/// let shared = Rc::new(42);
+/// {
+///     let bump = DropBump::new();
+///     let v = bump.alloc(vec![Rc::clone(&shared), Rc::clone(&shared)]);
+///     assert_eq!(v.len(), 2);
+///     assert_eq!(Rc::strong_count(&shared), 3);
+/// }
+///
+/// // Dropping the arena dropped the vector and its `Rc`s.
+/// assert_eq!(Rc::strong_count(&shared), 1);
+/// ```
+///
+/// [`Bump`]: struct.Bump.html
+pub struct DropBump<'a, A: BackingAllocator = Global> {
+    bump: Bump<A>,
+    // The entry of the most recently allocated value that needs to be
+    // dropped, linked to the entries of the ones allocated before it.
+    drop_list: Cell<Option<NonNull<DropEntry>>>,
+    // Invariant, so that a `&DropBump<'a>` can't be used to allocate values
+    // that borrow anything shorter-lived than `'a`.
+    lifetime: PhantomData<fn(&'a ()) -> &'a ()>,
+}
+
+impl DropBump<'_> {
+    /// Construct a new, empty arena that drops the values allocated in it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::DropBump::new();
+    /// # let _ = bump;
+    /// ```
+    pub fn new() -> Self {
+        Self::from_bump(Bump::new())
+    }
+
+    /// Construct a new arena that drops the values allocated in it, with the
+    /// specified byte capacity to bump allocate into.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::DropBump::with_capacity(100);
+    /// # let _ = bump;
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_bump(Bump::with_capacity(capacity))
+    }
+}
+
+impl<'a, A: BackingAllocator> DropBump<'a, A> {
+    /// Construct a new arena that drops the values allocated in it, and that
+    /// bump allocates into the given, empty `Bump`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{Bump, DropBump, Global};
+    ///
+    /// let bump = DropBump::from_bump(Bump::with_capacity_in(1024, Global));
+    /// # let _ = bump;
+    /// ```
+    pub fn from_bump(mut bump: Bump<A>) -> Self {
+        // Anything that is already in the arena would be overwritten.
+        bump.reset();
+        DropBump {
+            bump,
+            drop_list: Cell::new(None),
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Allocate an object in this arena and return an exclusive reference to
+    /// it. The object is dropped when the arena is reset or dropped.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::DropBump::new();
+    /// let s = bump.alloc(String::from("hello"));
+    /// s.push_str(" world");
+    /// assert_eq!(s, "hello world");
+    /// ```
+    #[inline(always)]
+    pub fn alloc<T: 'a>(&self, val: T) -> &mut T {
+        self.alloc_with(|| val)
+    }
+
+    /// Pre-allocate space for an object in this arena, initialize it using the
+    /// closure, then return an exclusive reference to it, as
+    /// [`Bump::alloc_with`] does. The object is dropped when the arena is
+    /// reset or dropped.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::DropBump::new();
+    /// let v = bump.alloc_with(|| vec![1, 2, 3]);
+    /// assert_eq!(v.iter().sum::<i32>(), 6);
+    /// ```
+    ///
+    /// [`Bump::alloc_with`]: struct.Bump.html#method.alloc_with
+    #[inline(always)]
+    pub fn alloc_with<F, T: 'a>(&self, f: F) -> &mut T
+    where
+        F: FnOnce() -> T,
+    {
+        if !mem::needs_drop::<T>() {
+            return self.bump.alloc_with(f);
+        }
+
+        let tracked: *mut Tracked<T> = self.bump.alloc_with(|| Tracked {
+            entry: DropEntry {
+                drop: drop_tracked::<T>,
+                prev: None,
+            },
+            value: f(),
+        });
+        unsafe {
+            self.push(NonNull::new_unchecked(tracked).cast());
+            &mut (*tracked).value
+        }
+    }
+
+    /// Allocate a slice of `len` elements, initialized by calling `f` with
+    /// the index of each element, as [`Bump::alloc_slice_fill_with`] does.
+    /// The elements are dropped when the arena is reset or dropped.
+    ///
+    /// If `f` panics, the elements that it already returned are leaked.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::DropBump::new();
+    /// let names = bump.alloc_slice_fill_with(3, |i| format!("name {}", i));
+    /// assert_eq!(names[2], "name 2");
+    /// ```
+    ///
+    /// [`Bump::alloc_slice_fill_with`]: struct.Bump.html#method.alloc_slice_fill_with
+    #[inline(always)]
+    pub fn alloc_slice_fill_with<T: 'a, F>(&self, len: usize, f: F) -> &mut [T]
+    where
+        F: FnMut(usize) -> T,
+    {
+        let values = self.bump.alloc_slice_fill_with(len, f);
+        if !mem::needs_drop::<T>() || values.is_empty() {
+            return values;
+        }
+
+        let ptr = values.as_mut_ptr();
+        let tracked: *mut TrackedSlice<T> = self.bump.alloc(TrackedSlice {
+            entry: DropEntry {
+                drop: drop_tracked_slice::<T>,
+                prev: None,
+            },
+            ptr: unsafe { NonNull::new_unchecked(ptr) },
+            len,
+        });
+        unsafe {
+            self.push(NonNull::new_unchecked(tracked).cast());
+            slice::from_raw_parts_mut(ptr, len)
+        }
+    }
+
+    /// `Clone` a slice into this arena and return an exclusive reference to
+    /// the clone, as [`Bump::alloc_slice_clone`] does. The clones are dropped
+    /// when the arena is reset or dropped.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::DropBump::new();
+    /// let originals = [String::from("a"), String::from("b")];
+    /// let clones = bump.alloc_slice_clone(&originals);
+    /// assert_eq!(originals, clones);
+    /// ```
+    ///
+    /// [`Bump::alloc_slice_clone`]: struct.Bump.html#method.alloc_slice_clone
+    #[inline(always)]
+    pub fn alloc_slice_clone<T: Clone + 'a>(&self, src: &[T]) -> &mut [T] {
+        self.alloc_slice_fill_with(src.len(), |i| src[i].clone())
+    }
+
+    /// Drop every value that was allocated through this arena's own
+    /// allocation methods, most recent first, and then reset the underlying
+    /// arena, as [`Bump::reset`] does.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    ///
+    /// let shared = Rc::new(());
+    /// let mut bump = bumpalo::DropBump::new();
+    /// bump.alloc(Rc::clone(&shared));
+    /// assert_eq!(Rc::strong_count(&shared), 2);
+    ///
+    /// bump.reset();
+    /// assert_eq!(Rc::strong_count(&shared), 1);
+    /// ```
+    ///
+    /// [`Bump::reset`]: struct.Bump.html#method.reset
+    pub fn reset(&mut self) {
+        self.drop_values();
+        self.bump.reset();
+    }
+
+    fn push(&self, entry: NonNull<DropEntry>) {
+        unsafe {
+            (*entry.as_ptr()).prev = self.drop_list.get();
+        }
+        self.drop_list.set(Some(entry));
+    }
+
+    fn drop_values(&mut self) {
+        // Take the whole list first, so that nothing is dropped twice if a
+        // destructor panics.
+        let mut next = self.drop_list.take();
+        while let Some(entry) = next {
+            unsafe {
+                next = entry.as_ref().prev;
+                (entry.as_ref().drop)(entry);
+            }
+        }
+    }
+}
+
+impl Default for DropBump<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: BackingAllocator> Deref for DropBump<'_, A> {
+    type Target = Bump<A>;
+
+    fn deref(&self) -> &Bump<A> {
+        &self.bump
+    }
+}
+
+impl<A: BackingAllocator> Drop for DropBump<'_, A> {
+    fn drop(&mut self) {
+        self.drop_values();
+    }
+}
+
+impl<A: BackingAllocator + fmt::Debug> fmt::Debug for DropBump<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DropBump")
+            .field("bump", &self.bump)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,384 @@
+//! A pool of per-thread arenas whose allocations share one lifetime.
+//!
+//! See [`Herd`] for details.
+//!
+//! [`Herd`]: ../struct.Herd.html
+
+use crate::Bump;
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+use core_alloc::alloc::Layout;
+use std::sync::{Mutex, PoisonError};
+use std::vec::Vec;
+
+/// A pool of arenas that hands out a [`Bump`] to each thread that asks for
+/// one, while keeping everything that is allocated in any of them alive for
+/// as long as the `Herd` itself.
+///
+/// This is an alternative to [`SyncBump`][crate::SyncBump] that avoids
+/// contention: each thread calls [`get`][Herd::get] to check out a
+/// [`Member`], and allocates from it as fast as from a `Bump` of its own. The
+/// values allocated through a `Member` borrow the `Herd` rather than the
+/// `Member`, so they can outlive it, and be sent to and read by other
+/// threads.
+///
+/// When a `Member` is dropped, its arena goes back to the herd, with all of
+/// its chunks, and is handed out again by a later call to `get`. Nothing is
+/// deallocated until the `Herd` is [`reset`][Herd::reset] or dropped.
+///
+/// This type is only available when the `std` cargo feature is enabled.
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::Herd;
+///
+/// let herd = Herd::new();
+/// let names: Vec<&str> = std::thread::scope(|s| {
+///     let handles: Vec<_> = (0..4)
+///         .map(|i| {
+///             let herd = &herd;
+///             s.spawn(move || {
+///                 let member = herd.get();
+///                 &*member.alloc_str(&format!("worker {}", i))
+///             })
+///         })
+///         .collect();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect()
+/// });
+/// assert_eq!(names, ["worker 0", "worker 1", "worker 2", "worker 3"]);
+/// ```
+///
+/// [`Bump`]: struct.Bump.html
+#[derive(Debug, Default)]
+pub struct Herd {
+    // The arenas that are not checked out by any `Member` right now. Moving
+    // an arena in and out of here doesn't move its chunks, so what was
+    // allocated in it stays put for as long as the herd does.
+    idle: Mutex<Vec<Bump>>,
+}
+
+impl Herd {
+    /// Construct a new, empty herd.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// # let _ = herd;
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check out an arena from this herd for the current thread to allocate
+    /// from.
+    ///
+    /// An arena that a dropped `Member` gave back is reused if there is one,
+    /// and otherwise a new, empty one is created.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let x = {
+    ///     let member = herd.get();
+    ///     member.alloc(42)
+    /// };
+    /// // The allocation outlives the `Member` it came from.
+    /// assert_eq!(*x, 42);
+    /// ```
+    pub fn get(&self) -> Member<'_> {
+        let bump = self
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_default();
+        Member {
+            bump: ManuallyDrop::new(bump),
+            herd: self,
+        }
+    }
+
+    /// Reset every arena in this herd, as [`Bump::reset`] does.
+    ///
+    /// Since this takes `&mut self`, every `Member` has given its arena back
+    /// by now, along with all of the chunks that it allocated. Each arena
+    /// keeps its most recent chunk to be reused, and returns the others to
+    /// the global allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut herd = bumpalo::Herd::new();
+    /// herd.get().alloc_slice_fill_copy(10_000, 0_u8);
+    /// herd.reset();
+    /// ```
+    ///
+    /// [`Bump::reset`]: struct.Bump.html#method.reset
+    pub fn reset(&mut self) {
+        let idle = self.idle.get_mut().unwrap_or_else(PoisonError::into_inner);
+        for bump in idle {
+            bump.reset();
+        }
+    }
+
+    /// Get the total number of bytes that the arenas in this herd have
+    /// allocated for their chunks, as [`Bump::allocated_bytes`] does.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut herd = bumpalo::Herd::new();
+    /// herd.get().alloc(1_u64);
+    /// assert!(herd.allocated_bytes() >= 8);
+    /// ```
+    ///
+    /// [`Bump::allocated_bytes`]: struct.Bump.html#method.allocated_bytes
+    pub fn allocated_bytes(&mut self) -> usize {
+        let idle = self.idle.get_mut().unwrap_or_else(PoisonError::into_inner);
+        idle.iter().map(|bump| bump.allocated_bytes()).sum()
+    }
+}
+
+/// An arena that is checked out of a [`Herd`] by a single thread.
+///
+/// Values allocated through a `Member` live as long as the `Herd` it came
+/// from, not just as long as the `Member` itself. A `Member` can be sent to
+/// another thread, but not shared between threads; each thread that wants to
+/// allocate should [`get`][Herd::get] a `Member` of its own.
+///
+/// Dropping a `Member` gives its arena back to the herd.
+///
+/// This type is only available when the `std` cargo feature is enabled.
+pub struct Member<'herd> {
+    bump: ManuallyDrop<Bump>,
+    herd: &'herd Herd,
+}
+
+impl<'herd> Member<'herd> {
+    /// Get the arena that this member allocates from.
+    ///
+    /// Allocations made directly through the returned `Bump` only live as
+    /// long as this borrow of the `Member`. Use the `Member`'s own allocation
+    /// methods for values that should live as long as the `Herd`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let member = herd.get();
+    /// let mut v = allocator_api2::vec::Vec::new_in(member.as_bump());
+    /// v.extend([1, 2, 3]);
+    /// assert_eq!(v, [1, 2, 3]);
+    /// ```
+    pub fn as_bump(&self) -> &Bump {
+        &self.bump
+    }
+
+    // Extend the lifetime of an allocation in this member's arena to that of
+    // the herd.
+    fn extend<T: ?Sized>(&self, value: &mut T) -> &'herd mut T {
+        // The arena's chunks are owned by the herd, which only deallocates
+        // them, or resets the arena, once it is no longer borrowed.
+        unsafe { &mut *(value as *mut T) }
+    }
+
+    /// Allocate an object, as [`Bump::alloc`] does, and return an exclusive
+    /// reference to it that lives as long as the herd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let x = herd.get().alloc("hello");
+    /// assert_eq!(*x, "hello");
+    /// ```
+    ///
+    /// [`Bump::alloc`]: struct.Bump.html#method.alloc
+    #[inline(always)]
+    pub fn alloc<T>(&self, val: T) -> &'herd mut T {
+        self.extend(self.bump.alloc(val))
+    }
+
+    /// Allocate an object that is initialized by `f`, as
+    /// [`Bump::alloc_with`] does, and return an exclusive reference to it
+    /// that lives as long as the herd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let x = herd.get().alloc_with(|| "hello");
+    /// assert_eq!(*x, "hello");
+    /// ```
+    ///
+    /// [`Bump::alloc_with`]: struct.Bump.html#method.alloc_with
+    #[inline(always)]
+    pub fn alloc_with<F, T>(&self, f: F) -> &'herd mut T
+    where
+        F: FnOnce() -> T,
+    {
+        self.extend(self.bump.alloc_with(f))
+    }
+
+    /// `Copy` a slice into this member's arena, as
+    /// [`Bump::alloc_slice_copy`] does, and return an exclusive reference to
+    /// the copy that lives as long as the herd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let x = herd.get().alloc_slice_copy(&[1, 2, 3]);
+    /// assert_eq!(x, &[1, 2, 3]);
+    /// ```
+    ///
+    /// [`Bump::alloc_slice_copy`]: struct.Bump.html#method.alloc_slice_copy
+    #[inline(always)]
+    pub fn alloc_slice_copy<T>(&self, src: &[T]) -> &'herd mut [T]
+    where
+        T: Copy,
+    {
+        self.extend(self.bump.alloc_slice_copy(src))
+    }
+
+    /// `Clone` a slice into this member's arena, as
+    /// [`Bump::alloc_slice_clone`] does, and return an exclusive reference to
+    /// the clone that lives as long as the herd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let x = herd.get().alloc_slice_clone(&["a", "b"]);
+    /// assert_eq!(x, &["a", "b"]);
+    /// ```
+    ///
+    /// [`Bump::alloc_slice_clone`]: struct.Bump.html#method.alloc_slice_clone
+    #[inline(always)]
+    pub fn alloc_slice_clone<T>(&self, src: &[T]) -> &'herd mut [T]
+    where
+        T: Clone,
+    {
+        self.extend(self.bump.alloc_slice_clone(src))
+    }
+
+    /// Allocate a slice of `len` elements that are initialized by `f`, as
+    /// [`Bump::alloc_slice_fill_with`] does, and return an exclusive
+    /// reference to it that lives as long as the herd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let x = herd.get().alloc_slice_fill_with(3, |i| i * 2);
+    /// assert_eq!(x, &[0, 2, 4]);
+    /// ```
+    ///
+    /// [`Bump::alloc_slice_fill_with`]: struct.Bump.html#method.alloc_slice_fill_with
+    #[inline(always)]
+    pub fn alloc_slice_fill_with<T, F>(&self, len: usize, f: F) -> &'herd mut [T]
+    where
+        F: FnMut(usize) -> T,
+    {
+        self.extend(self.bump.alloc_slice_fill_with(len, f))
+    }
+
+    /// Allocate a slice of `len` copies of `value`, as
+    /// [`Bump::alloc_slice_fill_copy`] does, and return an exclusive
+    /// reference to it that lives as long as the herd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let x = herd.get().alloc_slice_fill_copy(3, 7);
+    /// assert_eq!(x, &[7, 7, 7]);
+    /// ```
+    ///
+    /// [`Bump::alloc_slice_fill_copy`]: struct.Bump.html#method.alloc_slice_fill_copy
+    #[inline(always)]
+    pub fn alloc_slice_fill_copy<T: Copy>(&self, len: usize, value: T) -> &'herd mut [T] {
+        self.extend(self.bump.alloc_slice_fill_copy(len, value))
+    }
+
+    /// `Copy` a string slice into this member's arena, as
+    /// [`Bump::alloc_str`] does, and return an exclusive reference to it that
+    /// lives as long as the herd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the string fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let herd = bumpalo::Herd::new();
+    /// let hello = herd.get().alloc_str("hello world");
+    /// assert_eq!("hello world", hello);
+    /// ```
+    ///
+    /// [`Bump::alloc_str`]: struct.Bump.html#method.alloc_str
+    #[inline(always)]
+    pub fn alloc_str(&self, src: &str) -> &'herd mut str {
+        self.extend(self.bump.alloc_str(src))
+    }
+
+    /// Allocate space for an object with the given `Layout`, as
+    /// [`Bump::alloc_layout`] does. The space stays allocated for as long as
+    /// the herd lives.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space matching `layout` fails.
+    ///
+    /// [`Bump::alloc_layout`]: struct.Bump.html#method.alloc_layout
+    #[inline(always)]
+    pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
+        self.bump.alloc_layout(layout)
+    }
+}
+
+impl Drop for Member<'_> {
+    fn drop(&mut self) {
+        let bump = unsafe { ManuallyDrop::take(&mut self.bump) };
+        self.herd
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(bump);
+    }
+}
+
+impl fmt::Debug for Member<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Member").field("bump", &self.bump).finish()
+    }
+}
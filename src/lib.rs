@@ -13,24 +13,73 @@ pub mod boxed;
 pub mod collections;
 
 mod alloc;
-
+#[cfg(feature = "std")]
+#[macro_use]
+mod alloc_methods;
+mod budget;
+mod buffer;
+mod builder;
+mod c_str;
+mod debug_checks;
+mod drop;
+#[cfg(feature = "std")]
+mod herd;
+mod sanitize;
+mod stats;
+#[cfg(feature = "std")]
+mod sync;
+mod typed;
+mod zeroable;
+// Miri can't reserve address space without committing it.
+#[cfg(all(feature = "std", target_os = "linux", not(miri)))]
+mod vm;
+
+use budget::BudgetSlot;
 use core::cell::Cell;
-use core::fmt::Display;
+use core::ffi::CStr;
+use core::fmt::{self, Display};
 use core::iter;
 use core::marker::PhantomData;
 use core::mem;
 use core::ptr::{self, NonNull};
 use core::slice;
 use core::str;
-use core_alloc::alloc::{alloc, dealloc, Layout};
+use core_alloc::alloc::Layout;
+use debug_checks::RedZones;
+use sanitize::Poisoning;
+use stats::StatsCell;
+
+// The trait that a `Bump`'s backing allocator must implement. This is always
+// the `allocator-api2` version of the trait, even when we additionally
+// implement the nightly `Allocator` trait for `&Bump`.
+use allocator_api2::alloc::Allocator as BackingAllocator;
+pub use allocator_api2::alloc::Global;
 
 #[cfg(feature = "allocator_api")]
 use core_alloc::alloc::{AllocError, Allocator};
 
-#[cfg(all(feature = "allocator-api2", not(feature = "allocator_api")))]
+#[cfg(not(feature = "allocator_api"))]
 use allocator_api2::alloc::{AllocError, Allocator};
 
-pub use alloc::AllocErr;
+pub use alloc::{AllocErr, AllocErrKind};
+#[cfg(feature = "std")]
+pub use budget::AllocationBudget;
+pub use buffer::{Buffer, NoAlloc};
+pub use builder::BumpBuilder;
+pub use c_str::NulError;
+#[cfg(feature = "debug-checks")]
+pub use debug_checks::IntegrityError;
+pub use drop::DropBump;
+#[cfg(feature = "std")]
+pub use herd::{Herd, Member};
+#[cfg(feature = "stats")]
+pub use stats::Stats;
+#[cfg(feature = "std")]
+pub use sync::SyncBump;
+pub use typed::{TypedBump, TypedIter, TypedIterMut};
+#[cfg(all(feature = "std", target_os = "linux", not(miri)))]
+pub use vm::VmBump;
+pub use zeroable::Zeroable;
 
 /// An error returned from [`Bump::try_alloc_try_with`].
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -282,19 +331,186 @@ impl<E: Display> Display for AllocOrInitError<E> {
 /// assert_eq!(bump.allocation_limit(), None);
 /// ```
 ///
-/// #### Warning
+/// #### Errors
 ///
-/// Because of backwards compatibility, allocations that fail
-/// due to allocation limits will not present differently than
-/// errors due to resource exhaustion.
-
+/// Allocations that fail because of the allocation limit return an
+/// [`AllocErr`] whose [`kind`][AllocErr::kind] is
+/// [`AllocErrKind::AllocationLimit`], while those that fail because the
+/// backing allocator ran out of memory have [`AllocErrKind::Allocator`]:
+///
+/// ```
+/// use bumpalo::{AllocErrKind, Bump};
+///
+/// let bump = Bump::new();
+/// bump.set_allocation_limit(Some(1024));
+///
+/// let err = bump.try_alloc_layout(std::alloc::Layout::new::<[u8; 4096]>()).unwrap_err();
+/// assert_eq!(err.kind(), AllocErrKind::AllocationLimit);
+/// assert_eq!(err.headroom(), Some(1024));
+/// ```
+///
+/// ### Backing Allocators
+///
+/// By default, a `Bump` gets its chunks of memory from the global allocator.
+/// The `A` type parameter allows using any other
+/// [`allocator_api2::alloc::Allocator`][backing] for chunks instead, such as a
+/// memory pool or an allocator that counts its allocations. Use
+/// [`Bump::new_in`] or [`Bump::try_with_capacity_in`] to create such an arena.
+///
+/// ```
+/// use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+/// use bumpalo::Bump;
+/// use std::cell::Cell;
+/// use std::ptr::NonNull;
+///
+/// /// An allocator that counts how many chunks are currently allocated.
+/// #[derive(Default)]
+/// struct Counting(Cell<usize>);
+///
+/// unsafe impl Allocator for Counting {
+///     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+///         self.0.set(self.0.get() + 1);
+///         Global.allocate(layout)
+///     }
+///
+///     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+///         self.0.set(self.0.get() - 1);
+///         Global.deallocate(ptr, layout)
+///     }
+/// }
+///
+/// let bump = Bump::new_in(Counting::default());
+/// bump.alloc(42);
+/// assert_eq!(bump.allocator().0.get(), 1);
+/// ```
+///
+/// ### Minimum Alignment
+///
+/// By default, each allocation is aligned to exactly what its type or
+/// `Layout` requires, so the bump finger has to be rounded down to the
+/// requested alignment on every allocation. If most of your allocations share
+/// an alignment, you can instead pick a `MIN_ALIGN` for the arena: the bump
+/// finger is then always kept aligned to `MIN_ALIGN`, allocations that need at
+/// most that alignment skip the rounding entirely, and their sizes are rounded
+/// up to a multiple of `MIN_ALIGN` instead. `MIN_ALIGN` must be a power of two
+/// that is no greater than 16.
+///
+/// ```
+/// use bumpalo::{Bump, Global};
+///
+/// let bump = Bump::<Global, 8>::with_min_align();
+///
+/// // Every allocation is 8-aligned, even a single byte.
+/// let a = bump.alloc(1_u8) as *mut u8 as usize;
+/// let b = bump.alloc(2_u64) as *mut u64 as usize;
+/// assert_eq!(a % 8, 0);
+/// assert_eq!(b % 8, 0);
+/// ```
+///
+/// To combine a minimum alignment with a custom backing allocator, use
+/// [`Bump::builder`] and its [`min_align`][BumpBuilder::min_align] and
+/// [`allocator`][BumpBuilder::allocator] methods.
+///
+/// ### Upward Bumping
+///
+/// By default, a `Bump` bumps downwards: each chunk is filled from its end
+/// towards its start, which keeps the fast path for allocating as small as
+/// possible. The downside is that growing the most recent allocation, for
+/// example when a vector that was the last thing allocated in the arena needs
+/// more capacity, must move its contents to a new, lower address.
+///
+/// An [`UpBump`], which is a `Bump` whose `UP` parameter is `true`, instead
+/// fills each chunk from its start towards its end. The most recent
+/// allocation then ends at the bump finger, so growing or shrinking it only
+/// moves the finger and never copies any bytes, as long as there is room left
+/// in the current chunk.
+///
+/// ```
+/// use allocator_api2::vec::Vec;
+/// use bumpalo::Bump;
+///
+/// let bump = Bump::upward();
+/// let mut v = Vec::with_capacity_in(1, &bump);
+/// v.push(0_u32);
+/// let start = v.as_ptr();
+///
+/// // Growing the vector keeps it in place.
+/// v.extend(1..100);
+/// assert_eq!(v.as_ptr(), start);
+/// ```
+///
+/// Use [`Bump::upward`] and friends to create an upward bumping arena, or
+/// [`BumpBuilder::upward`] to combine it with other settings.
+///
+/// ### Caller-Provided Buffers
+///
+/// [`Bump::from_buffer`] creates an arena that allocates out of a buffer that
+/// you provide, and fails to allocate once it is full instead of getting more
+/// memory from the global allocator. [`Bump::from_buffer_in`] falls back to
+/// another allocator instead, and [`BumpBuilder::build_with_buffer`] combines
+/// a buffer with other settings.
+///
+/// ```
+/// use bumpalo::Bump;
+/// use core::mem::MaybeUninit;
+///
+/// let mut buffer = [MaybeUninit::uninit(); 4096];
+/// let bump = Bump::from_buffer(&mut buffer);
+/// let xs = bump.alloc_slice_copy(&[1, 2, 3]);
+/// assert_eq!(xs, &[1, 2, 3]);
+/// ```
+///
+/// Note that the [`bumpalo::collections`] and [`bumpalo::boxed`] types can
+/// only be used with `Bump`s that are backed by the global allocator, have the
+/// default `MIN_ALIGN` of 1, and bump downwards.
+///
+/// [backing]: https://docs.rs/allocator-api2/latest/allocator_api2/alloc/trait.Allocator.html
+/// [`bumpalo::collections`]: collections/index.html
+/// [`bumpalo::boxed`]: boxed/index.html
 #[derive(Debug)]
-pub struct Bump {
+pub struct Bump<A: BackingAllocator = Global, const MIN_ALIGN: usize = 1, const UP: bool = false> {
     // The current chunk we are bump allocating within.
     current_chunk_footer: Cell<NonNull<ChunkFooter>>,
+    // Empty chunks that `reset_with` kept around to be bumped into again,
+    // linked through their `prev` links. The first one is refilled next.
+    spare_chunks: Cell<NonNull<ChunkFooter>>,
+    // The total capacity of `spare_chunks`.
+    spare_bytes: Cell<usize>,
+    // The bytes in use in the chunks before the current one. Only the last
+    // allocation can be deallocated, shrunk or grown in place, and it is
+    // always in the current chunk, so this only changes when chunks are
+    // pushed or popped.
+    prev_chunks_used_bytes: Cell<usize>,
+    // The most bytes that were in use at once since the last reset, not
+    // counting what is in use right now.
+    high_water_mark: Cell<usize>,
     allocation_limit: Cell<Option<usize>>,
+    // What infallible allocations do when they fail.
+    // It gets the arena as a trait object, so that the arena stays covariant
+    // in `A`.
+    oom_handler: Cell<fn(&dyn OomContext, Layout) -> OomAction>,
+    // The budget shared with other arenas that chunks are drawn from.
+    budget: BudgetSlot,
+    // Allocation statistics, when the `stats` feature is enabled.
+    stats: StatsCell,
+    // The red zones of live allocations, when the `debug-checks` feature is
+    // enabled.
+    #[cfg_attr(not(feature = "debug-checks"), allow(dead_code))]
+    red_zones: RedZones,
+    // Which memory is poisoned, when the `sanitize` feature is enabled.
+    poisoning: Poisoning,
+    // How to size new chunks.
+    chunk_policy: ChunkPolicy,
+    // The allocator that chunks are allocated from and returned to.
+    allocator: A,
 }
 
+/// An arena that bumps its allocations upwards, towards higher addresses.
+///
+/// See [the `Bump` documentation](struct.Bump.html#upward-bumping) for
+/// details.
+pub type UpBump<A = Global, const MIN_ALIGN: usize = 1> = Bump<A, MIN_ALIGN, true>;
+
 #[repr(C)]
 #[derive(Debug)]
 struct ChunkFooter {
@@ -312,6 +528,8 @@ struct ChunkFooter {
     prev: Cell<NonNull<ChunkFooter>>,
 
     // Bump allocation finger that is always in the range `self.data..=self`.
+    // It starts at `self` and moves down towards `self.data` in downward
+    // bumping arenas, and the other way around in upward bumping ones.
     ptr: Cell<NonNull<u8>>,
 
     // The bytes allocated in all chunks so far, the canonical empty chunk has
@@ -326,7 +544,10 @@ struct ChunkFooter {
 /// For the canonical empty chunk to be `static`, its type must be `Sync`, which
 /// is the purpose of this wrapper type. This is safe because the empty chunk is
 /// immutable and never actually modified.
-#[repr(transparent)]
+///
+/// It is aligned to `CHUNK_ALIGN`, like the end of every other chunk, so that
+/// its bump finger satisfies any `Bump`'s `MIN_ALIGN`.
+#[repr(C, align(16))]
 struct EmptyChunkFooter(ChunkFooter);
 
 unsafe impl Sync for EmptyChunkFooter {}
@@ -361,14 +582,27 @@ impl EmptyChunkFooter {
 
 impl ChunkFooter {
     // Returns the start and length of the currently allocated region of this
-    // chunk.
-    fn as_raw_parts(&self) -> (*const u8, usize) {
+    // chunk. Downward-bumping arenas allocate between the bump finger and the
+    // footer, upward-bumping ones between the start of the chunk and the bump
+    // finger.
+    fn as_raw_parts(&self, upward: bool) -> (*const u8, usize) {
         let data = self.data.as_ptr() as *const u8;
         let ptr = self.ptr.get().as_ptr() as *const u8;
         debug_assert!(data <= ptr);
         debug_assert!(ptr <= self as *const ChunkFooter as *const u8);
-        let len = unsafe { (self as *const ChunkFooter as *const u8).offset_from(ptr) as usize };
-        (ptr, len)
+        if upward {
+            let len = unsafe { ptr.offset_from(data) as usize };
+            (data, len)
+        } else {
+            let len =
+                unsafe { (self as *const ChunkFooter as *const u8).offset_from(ptr) as usize };
+            (ptr, len)
+        }
+    }
+
+    // The size of this chunk, not counting its footer.
+    fn capacity(&self) -> usize {
+        self.layout.size() - FOOTER_SIZE
     }
 
     /// Is this chunk the last empty chunk?
@@ -377,26 +611,34 @@ impl ChunkFooter {
     }
 }
 
-impl Default for Bump {
-    fn default() -> Bump {
-        Bump::new()
+impl<A: BackingAllocator + Default, const MIN_ALIGN: usize, const UP: bool> Default
+    for Bump<A, MIN_ALIGN, UP>
+{
+    fn default() -> Self {
+        Self::try_with_capacity_and_policy_in(0, ChunkPolicy::DEFAULT, A::default())
+            .unwrap_or_else(|_| oom())
     }
 }
 
-impl Drop for Bump {
+impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> Drop for Bump<A, MIN_ALIGN, UP> {
     fn drop(&mut self) {
+        // Don't panic about a corrupted arena while already panicking.
+        #[cfg(feature = "debug-checks")]
+        let integrity = if thread_is_panicking() {
+            Ok(())
+        } else {
+            self.check_integrity()
+        };
+
         unsafe {
-            dealloc_chunk_list(self.current_chunk_footer.get());
+            self.dealloc_chunk_list(self.current_chunk_footer.get());
+            self.dealloc_chunk_list(self.spare_chunks.get());
         }
-    }
-}
 
-#[inline]
-unsafe fn dealloc_chunk_list(mut footer: NonNull<ChunkFooter>) {
-    while !footer.as_ref().is_empty() {
-        let f = footer;
-        footer = f.as_ref().prev.get();
-        dealloc(f.as_ref().data.as_ptr(), f.as_ref().layout);
+        #[cfg(feature = "debug-checks")]
+        if let Err(e) = integrity {
+            panic!("{}", e);
+        }
     }
 }
 
@@ -404,7 +646,10 @@ unsafe fn dealloc_chunk_list(mut footer: NonNull<ChunkFooter>) {
 // chunks until you start allocating from it. But by the time you allocate from
 // it, the returned references to allocations borrow the `Bump` and therefore
 // prevent sending the `Bump` across threads until the borrows end.
-unsafe impl Send for Bump {}
+unsafe impl<A: BackingAllocator + Send, const MIN_ALIGN: usize, const UP: bool> Send
+    for Bump<A, MIN_ALIGN, UP>
+{
+}
 
 #[inline]
 fn is_pointer_aligned_to<T>(pointer: *mut T, align: usize) -> bool {
@@ -448,6 +693,7 @@ const FOOTER_SIZE: usize = mem::size_of::<ChunkFooter>();
 // Assert that ChunkFooter is at most the supported alignment. This will give a compile time error if it is not the case
 const _FOOTER_ALIGN_ASSERTION: bool = mem::align_of::<ChunkFooter>() <= CHUNK_ALIGN;
 const _: [(); _FOOTER_ALIGN_ASSERTION as usize] = [()];
+const _: [(); (mem::align_of::<EmptyChunkFooter>() == CHUNK_ALIGN) as usize] = [()];
 
 // Maximum typical overhead per allocation imposed by allocators.
 const MALLOC_OVERHEAD: usize = 16;
@@ -470,6 +716,52 @@ const FIRST_ALLOCATION_GOAL: usize = 1 << 9;
 // take the alignment into account.
 const DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER: usize = FIRST_ALLOCATION_GOAL - OVERHEAD;
 
+/// How a `Bump` sizes the new chunks that it allocates. Configured through
+/// [`BumpBuilder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ChunkPolicy {
+    // Each new chunk is this many times as big as the previous one.
+    growth_factor: usize,
+    // New chunks are never bigger than this, unless they have to be in order
+    // to satisfy a single allocation request.
+    max_chunk_size: Option<usize>,
+    // When set, every chunk is this size, unless it has to be bigger in order
+    // to satisfy a single allocation request.
+    fixed_chunk_size: Option<usize>,
+}
+
+impl ChunkPolicy {
+    const DEFAULT: ChunkPolicy = ChunkPolicy {
+        growth_factor: 2,
+        max_chunk_size: None,
+        fixed_chunk_size: None,
+    };
+
+    /// The smallest size, without footer, that we will allocate a new chunk
+    /// with, unless we are bumping up against the allocation limit.
+    fn min_chunk_size(&self) -> usize {
+        let size = match (self.fixed_chunk_size, self.max_chunk_size) {
+            (Some(fixed), _) => fixed,
+            (None, Some(max)) => max.min(DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER),
+            (None, None) => DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER,
+        };
+        size.max(CHUNK_ALIGN)
+    }
+
+    /// The size, without footer, that we would like the chunk after a chunk of
+    /// `prev_size` bytes to have.
+    fn next_chunk_size(&self, prev_size: usize) -> Option<usize> {
+        if let Some(fixed) = self.fixed_chunk_size {
+            return Some(fixed);
+        }
+        let size = prev_size.checked_mul(self.growth_factor)?;
+        Some(match self.max_chunk_size {
+            Some(max) => size.min(max),
+            None => size,
+        })
+    }
+}
+
 /// The memory size and alignment details for a potential new chunk
 /// allocation.
 #[derive(Debug, Clone, Copy)]
@@ -482,7 +774,7 @@ struct NewChunkMemoryDetails {
 /// Wrapper around `Layout::from_size_align` that adds debug assertions.
 #[inline]
 fn layout_from_size_align(size: usize, align: usize) -> Result<Layout, AllocErr> {
-    Layout::from_size_align(size, align).map_err(|_| AllocErr)
+    Layout::from_size_align(size, align).map_err(|_| AllocErr::size_overflow())
 }
 
 #[inline(never)]
@@ -536,10 +828,227 @@ impl Bump {
     /// # let _ = bump.unwrap();
     /// ```
     pub fn try_with_capacity(capacity: usize) -> Result<Self, AllocErr> {
+        Bump::try_with_capacity_in(capacity, Global)
+    }
+
+    /// Create a [`BumpBuilder`] for configuring a new arena, for example to
+    /// control how big its chunks get.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::builder()
+    ///     .growth_factor(4)
+    ///     .max_chunk_size(Some(1 << 20))
+    ///     .build();
+    /// # let _ = bump;
+    /// ```
+    pub fn builder() -> BumpBuilder {
+        BumpBuilder::new()
+    }
+}
+
+impl UpBump {
+    /// Construct a new, upward bumping arena to bump allocate into.
+    ///
+    /// See [the top-level documentation](struct.Bump.html#upward-bumping) for
+    /// details.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::upward();
+    /// # let _: bumpalo::UpBump = bump;
+    /// ```
+    pub fn upward() -> UpBump {
+        UpBump::upward_with_capacity(0)
+    }
+
+    /// Attempt to construct a new, upward bumping arena to bump allocate
+    /// into.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::try_upward();
+    /// # let _ = bump.unwrap();
+    /// ```
+    pub fn try_upward() -> Result<UpBump, AllocErr> {
+        UpBump::try_upward_with_capacity(0)
+    }
+
+    /// Construct a new, upward bumping arena with the specified byte capacity
+    /// to bump allocate into.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::upward_with_capacity(100);
+    /// # let _ = bump;
+    /// ```
+    pub fn upward_with_capacity(capacity: usize) -> UpBump {
+        UpBump::try_upward_with_capacity(capacity).unwrap_or_else(|_| oom())
+    }
+
+    /// Attempt to construct a new, upward bumping arena with the specified
+    /// byte capacity to bump allocate into.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::try_upward_with_capacity(100);
+    /// # let _ = bump.unwrap();
+    /// ```
+    pub fn try_upward_with_capacity(capacity: usize) -> Result<UpBump, AllocErr> {
+        UpBump::try_with_capacity_and_policy_in(capacity, ChunkPolicy::DEFAULT, Global)
+    }
+}
+
+impl<const MIN_ALIGN: usize, const UP: bool> Bump<Global, MIN_ALIGN, UP> {
+    /// Construct a new arena to bump allocate into, which keeps all
+    /// allocations aligned to at least `MIN_ALIGN`.
+    ///
+    /// See [the top-level documentation](struct.Bump.html#minimum-alignment)
+    /// for details.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::<bumpalo::Global, 8>::with_min_align();
+    /// # let _ = bump;
+    /// ```
+    pub fn with_min_align() -> Self {
+        Self::with_min_align_and_capacity(0)
+    }
+
+    /// Attempt to construct a new arena to bump allocate into, which keeps
+    /// all allocations aligned to at least `MIN_ALIGN`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::<bumpalo::Global, 8>::try_with_min_align();
+    /// # let _ = bump.unwrap();
+    /// ```
+    pub fn try_with_min_align() -> Result<Self, AllocErr> {
+        Self::try_with_min_align_and_capacity(0)
+    }
+
+    /// Construct a new arena with the specified byte capacity to bump
+    /// allocate into, which keeps all allocations aligned to at least
+    /// `MIN_ALIGN`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::<bumpalo::Global, 8>::with_min_align_and_capacity(100);
+    /// # let _ = bump;
+    /// ```
+    pub fn with_min_align_and_capacity(capacity: usize) -> Self {
+        Self::try_with_min_align_and_capacity(capacity).unwrap_or_else(|_| oom())
+    }
+
+    /// Attempt to construct a new arena with the specified byte capacity to
+    /// bump allocate into, which keeps all allocations aligned to at least
+    /// `MIN_ALIGN`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::<bumpalo::Global, 8>::try_with_min_align_and_capacity(100);
+    /// # let _ = bump.unwrap();
+    /// ```
+    pub fn try_with_min_align_and_capacity(capacity: usize) -> Result<Self, AllocErr> {
+        Self::try_with_capacity_and_policy_in(capacity, ChunkPolicy::DEFAULT, Global)
+    }
+}
+
+impl<A: BackingAllocator> Bump<A> {
+    /// Construct a new arena to bump allocate into, whose chunks are allocated
+    /// from the given allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new_in(allocator_api2::alloc::Global);
+    /// # let _ = bump;
+    /// ```
+    pub fn new_in(allocator: A) -> Self {
+        Self::with_capacity_in(0, allocator)
+    }
+
+    /// Attempt to construct a new arena to bump allocate into, whose chunks
+    /// are allocated from the given allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::try_new_in(allocator_api2::alloc::Global);
+    /// # let _ = bump.unwrap();
+    /// ```
+    pub fn try_new_in(allocator: A) -> Result<Self, AllocErr> {
+        Self::try_with_capacity_in(0, allocator)
+    }
+
+    /// Construct a new arena with the specified byte capacity to bump allocate
+    /// into, whose chunks are allocated from the given allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::with_capacity_in(100, allocator_api2::alloc::Global);
+    /// # let _ = bump;
+    /// ```
+    pub fn with_capacity_in(capacity: usize, allocator: A) -> Self {
+        Self::try_with_capacity_in(capacity, allocator).unwrap_or_else(|_| oom())
+    }
+
+    /// Attempt to construct a new arena with the specified byte capacity to
+    /// bump allocate into, whose chunks are allocated from the given
+    /// allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::try_with_capacity_in(100, allocator_api2::alloc::Global);
+    /// # let _ = bump.unwrap();
+    /// ```
+    pub fn try_with_capacity_in(capacity: usize, allocator: A) -> Result<Self, AllocErr> {
+        Self::try_with_capacity_and_policy_in(capacity, ChunkPolicy::DEFAULT, allocator)
+    }
+}
+
+impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> Bump<A, MIN_ALIGN, UP> {
+    // Evaluated when a `Bump` with a particular `MIN_ALIGN` is created, turning
+    // unsupported minimum alignments into compile-time errors.
+    const MIN_ALIGN_IS_VALID: () = assert!(
+        MIN_ALIGN.is_power_of_two() && MIN_ALIGN <= CHUNK_ALIGN,
+        "`MIN_ALIGN` must be a power of two that is no greater than 16"
+    );
+
+    pub(crate) fn try_with_capacity_and_policy_in(
+        capacity: usize,
+        chunk_policy: ChunkPolicy,
+        allocator: A,
+    ) -> Result<Self, AllocErr> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::MIN_ALIGN_IS_VALID;
+
         if capacity == 0 {
             return Ok(Bump {
                 current_chunk_footer: Cell::new(EMPTY_CHUNK.get()),
+                spare_chunks: Cell::new(EMPTY_CHUNK.get()),
+                spare_bytes: Cell::new(0),
+                prev_chunks_used_bytes: Cell::new(0),
+                high_water_mark: Cell::new(0),
                 allocation_limit: Cell::new(None),
+                oom_handler: Cell::new(|_, _| OomAction::Panic),
+                budget: BudgetSlot::default(),
+                stats: StatsCell::default(),
+                red_zones: RedZones::default(),
+                poisoning: Poisoning::default(),
+                chunk_policy,
+                allocator,
             });
         }
 
@@ -547,17 +1056,47 @@ impl Bump {
 
         let chunk_footer = unsafe {
             Self::new_chunk(
-                Bump::new_chunk_memory_details(None, layout).ok_or(AllocErr)?,
+                &allocator,
+                Self::new_chunk_memory_details(None, layout, &chunk_policy)
+                    .ok_or_else(|| AllocErr::new(AllocErrKind::SizeOverflow, Some(layout), None))?,
                 layout,
                 EMPTY_CHUNK.get(),
+                false,
             )
-            .ok_or(AllocErr)?
+            .ok_or_else(|| AllocErr::new(AllocErrKind::Allocator, Some(layout), None))?
         };
 
-        Ok(Bump {
+        let bump = Bump {
             current_chunk_footer: Cell::new(chunk_footer),
+            spare_chunks: Cell::new(EMPTY_CHUNK.get()),
+            spare_bytes: Cell::new(0),
+            prev_chunks_used_bytes: Cell::new(0),
+            high_water_mark: Cell::new(0),
             allocation_limit: Cell::new(None),
-        })
+            oom_handler: Cell::new(|_, _| OomAction::Panic),
+            budget: BudgetSlot::default(),
+            stats: StatsCell::default(),
+            red_zones: RedZones::default(),
+            poisoning: Poisoning::default(),
+            chunk_policy,
+            allocator,
+        };
+        bump.stats.update(|s| s.chunks_allocated += 1);
+        unsafe { bump.poison_free_space(chunk_footer.as_ref()) };
+        Ok(bump)
+    }
+
+    /// Get a reference to the allocator that this arena's chunks are allocated
+    /// from.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let _global: &allocator_api2::alloc::Global = bump.allocator();
+    /// ```
+    pub fn allocator(&self) -> &A {
+        &self.allocator
     }
 
     /// The allocation limit for this arena in bytes.
@@ -600,6 +1139,94 @@ impl Bump {
         self.allocation_limit.set(limit);
     }
 
+    /// The budget that this arena draws its chunks from, if any.
+    ///
+    /// This method is only available with the `std` cargo feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut bump = bumpalo::Bump::new();
+    /// assert!(bump.allocation_budget().is_none());
+    ///
+    /// bump.set_allocation_budget(Some(bumpalo::AllocationBudget::new(1024)));
+    /// assert_eq!(bump.allocation_budget().unwrap().limit(), 1024);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn allocation_budget(&self) -> Option<&AllocationBudget> {
+        self.budget.get()
+    }
+
+    /// Set the budget, shared with other arenas, that this arena draws its
+    /// chunks from. `None` means that this arena has no budget. See
+    /// [`AllocationBudget`] for details.
+    ///
+    /// The arena's existing chunks are returned to its previous budget and
+    /// drawn from the new one, even if they don't fit in it. In that case,
+    /// the arena can't get more chunks until enough chunks were returned to
+    /// the budget.
+    ///
+    /// This method is only available with the `std` cargo feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{AllocationBudget, Bump};
+    ///
+    /// let budget = AllocationBudget::new(1024 * 1024);
+    /// let mut bump = Bump::with_capacity(1000);
+    /// bump.set_allocation_budget(Some(budget.clone()));
+    /// assert_eq!(budget.used(), bump.allocated_bytes_including_metadata());
+    ///
+    /// bump.set_allocation_budget(None);
+    /// assert_eq!(budget.used(), 0);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn set_allocation_budget(&mut self, budget: Option<AllocationBudget>) {
+        let chunks = self.iter_footers().map(|f| unsafe { &*f.as_ptr() });
+        let bytes = chunks
+            .chain(self.iter_spare_chunks())
+            .filter(|f| !f.is_empty())
+            .map(|f| f.layout.size())
+            .sum();
+        self.budget.replace(budget, bytes);
+    }
+
+    /// Set the handler that decides what allocations that can't fail, such as
+    /// [`alloc`][Bump::alloc] or pushing onto a
+    /// [`collections::Vec`](collections/vec/struct.Vec.html), do when this
+    /// arena runs out of memory.
+    ///
+    /// The handler gets this arena, as an [`OomContext`], and the layout of
+    /// the allocation that failed. It can log them, free memory elsewhere or raise the
+    /// allocation limit, and then return the [`OomAction`] to take: retry
+    /// the allocation, panic, or abort the process. The handler is called
+    /// again each time a retry fails.
+    ///
+    /// By default, allocations that can't fail panic when they run out of
+    /// memory. Fallible methods like [`try_alloc`][Bump::try_alloc] never
+    /// call the handler.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{Bump, OomAction};
+    ///
+    /// let bump = Bump::new();
+    /// bump.set_allocation_limit(Some(0));
+    /// bump.set_oom_handler(|bump, layout| {
+    ///     eprintln!("raising the allocation limit to allocate {:?}", layout);
+    ///     bump.set_allocation_limit(None);
+    ///     OomAction::Retry
+    /// });
+    ///
+    /// assert_eq!(*bump.alloc(42), 42);
+    /// assert_eq!(bump.allocation_limit(), None);
+    /// ```
+    pub fn set_oom_handler(&self, handler: fn(&dyn OomContext, Layout) -> OomAction) {
+        self.oom_handler.set(handler);
+    }
+
     /// How much headroom an arena has before it hits its allocation
     /// limit.
     fn allocation_limit_remaining(&self) -> Option<usize> {
@@ -632,9 +1259,10 @@ impl Bump {
     fn new_chunk_memory_details(
         new_size_without_footer: Option<usize>,
         requested_layout: Layout,
+        chunk_policy: &ChunkPolicy,
     ) -> Option<NewChunkMemoryDetails> {
         let mut new_size_without_footer =
-            new_size_without_footer.unwrap_or(DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER);
+            new_size_without_footer.unwrap_or_else(|| chunk_policy.min_chunk_size());
 
         // We want to have CHUNK_ALIGN or better alignment
         let mut align = CHUNK_ALIGN;
@@ -646,19 +1274,34 @@ impl Bump {
             round_up_to(requested_layout.size(), align).unwrap_or_else(allocation_size_overflow);
         new_size_without_footer = new_size_without_footer.max(requested_size);
 
-        // We want our allocations to play nice with the memory allocator,
-        // and waste as little memory as possible.
-        // For small allocations, this means that the entire allocation
-        // including the chunk footer and mallocs internal overhead is
-        // as close to a power of two as we can go without going over.
-        // For larger allocations, we only need to get close to a page
-        // boundary without going over.
-        if new_size_without_footer < PAGE_STRATEGY_CUTOFF {
-            new_size_without_footer =
-                (new_size_without_footer + OVERHEAD).next_power_of_two() - OVERHEAD;
+        if chunk_policy.fixed_chunk_size.is_some() {
+            // Fixed-size chunks are exactly the size that was asked for, only
+            // rounded up to keep the footer aligned.
+            new_size_without_footer = round_up_to(new_size_without_footer, CHUNK_ALIGN)?;
         } else {
-            new_size_without_footer =
-                round_up_to(new_size_without_footer + OVERHEAD, 0x1000)? - OVERHEAD;
+            // We want our allocations to play nice with the memory allocator,
+            // and waste as little memory as possible.
+            // For small allocations, this means that the entire allocation
+            // including the chunk footer and mallocs internal overhead is
+            // as close to a power of two as we can go without going over.
+            // For larger allocations, we only need to get close to a page
+            // boundary without going over.
+            if new_size_without_footer < PAGE_STRATEGY_CUTOFF {
+                new_size_without_footer =
+                    (new_size_without_footer + OVERHEAD).next_power_of_two() - OVERHEAD;
+            } else {
+                new_size_without_footer =
+                    round_up_to(new_size_without_footer + OVERHEAD, 0x1000)? - OVERHEAD;
+            }
+
+            // Rounding must not push the chunk over the maximum chunk size,
+            // but a single request that is bigger than the maximum still gets
+            // a chunk of its own.
+            if let Some(max) = chunk_policy.max_chunk_size {
+                if new_size_without_footer > max {
+                    new_size_without_footer = round_down_to(max, CHUNK_ALIGN).max(requested_size);
+                }
+            }
         }
 
         debug_assert_eq!(align % CHUNK_ALIGN, 0);
@@ -679,10 +1322,15 @@ impl Bump {
     /// If given, `layouts` is a tuple of the current chunk size and the
     /// layout of the allocation request that triggered us to fall back to
     /// allocating a new chunk of memory.
+    ///
+    /// If `zeroed` is true, the chunk's memory is zeroed by the backing
+    /// allocator.
     unsafe fn new_chunk(
+        allocator: &A,
         new_chunk_memory_details: NewChunkMemoryDetails,
         requested_layout: Layout,
         prev: NonNull<ChunkFooter>,
+        zeroed: bool,
     ) -> Option<NonNull<ChunkFooter>> {
         let NewChunkMemoryDetails {
             new_size_without_footer,
@@ -694,35 +1342,117 @@ impl Bump {
 
         debug_assert!(size >= requested_layout.size());
 
-        let data = alloc(layout);
-        let data = NonNull::new(data)?;
-
-        // The `ChunkFooter` is at the end of the chunk.
-        let footer_ptr = data.as_ptr().add(new_size_without_footer);
+        let data = if zeroed {
+            allocator.allocate_zeroed(layout)
+        } else {
+            allocator.allocate(layout)
+        };
+        let data = data.ok()?.cast::<u8>();
         debug_assert_eq!((data.as_ptr() as usize) % align, 0);
-        debug_assert_eq!(footer_ptr as usize % CHUNK_ALIGN, 0);
-        let footer_ptr = footer_ptr as *mut ChunkFooter;
-
-        // The bump pointer is initialized to the end of the range we will
-        // bump out of.
-        let ptr = Cell::new(NonNull::new_unchecked(footer_ptr as *mut u8));
 
-        // The `allocated_bytes` of a new chunk counts the total size
-        // of the chunks, not how much of the chunks are used.
-        let allocated_bytes = prev.as_ref().allocated_bytes + new_size_without_footer;
+        Some(Self::init_chunk(
+            data,
+            layout,
+            new_size_without_footer,
+            prev,
+        ))
+    }
 
-        ptr::write(
-            footer_ptr,
-            ChunkFooter {
-                data,
-                layout,
-                prev: Cell::new(prev),
-                ptr,
+    /// Allocate a new chunk, drawing it from this arena's allocation budget,
+    /// and count it in the statistics.
+    unsafe fn new_budgeted_chunk(
+        &self,
+        new_chunk_memory_details: NewChunkMemoryDetails,
+        requested_layout: Layout,
+        prev: NonNull<ChunkFooter>,
+        zeroed: bool,
+    ) -> Result<NonNull<ChunkFooter>, AllocErrKind> {
+        let size = new_chunk_memory_details.size;
+        if !self.budget.try_charge(size) {
+            return Err(AllocErrKind::AllocationBudget);
+        }
+        match Self::new_chunk(
+            &self.allocator,
+            new_chunk_memory_details,
+            requested_layout,
+            prev,
+            zeroed,
+        ) {
+            Some(footer) => {
+                self.stats.update(|s| s.chunks_allocated += 1);
+                self.poison_free_space(footer.as_ref());
+                Ok(footer)
+            }
+            None => {
+                self.budget.refund(size);
+                Err(AllocErrKind::Allocator)
+            }
+        }
+    }
+
+    /// Return a chunk to the backing allocator and to this arena's allocation
+    /// budget.
+    unsafe fn dealloc_chunk(&self, footer: NonNull<ChunkFooter>) {
+        let layout = footer.as_ref().layout;
+        self.poisoning
+            .unpoison(footer.as_ref().data.as_ptr(), layout.size());
+        self.allocator.deallocate(footer.as_ref().data, layout);
+        self.budget.refund(layout.size());
+        self.stats.update(|s| s.chunks_freed += 1);
+    }
+
+    /// Deallocate the chunk that `footer` belongs to and every chunk that it
+    /// links to.
+    unsafe fn dealloc_chunk_list(&self, mut footer: NonNull<ChunkFooter>) {
+        while !footer.as_ref().is_empty() {
+            let f = footer;
+            footer = f.as_ref().prev.get();
+            self.dealloc_chunk(f);
+        }
+    }
+
+    /// Write the footer of a chunk whose memory, described by `data` and
+    /// `layout`, has just been obtained, and return it.
+    ///
+    /// The footer goes at `data + size_without_footer`, which must be aligned
+    /// to `CHUNK_ALIGN` and leave room for the footer within the chunk.
+    unsafe fn init_chunk(
+        data: NonNull<u8>,
+        layout: Layout,
+        size_without_footer: usize,
+        prev: NonNull<ChunkFooter>,
+    ) -> NonNull<ChunkFooter> {
+        // The `ChunkFooter` is at the end of the chunk.
+        let footer_ptr = data.as_ptr().add(size_without_footer);
+        debug_assert_eq!(footer_ptr as usize % CHUNK_ALIGN, 0);
+        debug_assert_eq!(size_without_footer + FOOTER_SIZE, layout.size());
+        let footer_ptr = footer_ptr as *mut ChunkFooter;
+
+        // The bump pointer is initialized to the end of the range we will
+        // bump out of when bumping downwards, and to its start when bumping
+        // upwards.
+        let ptr = Cell::new(if UP {
+            data
+        } else {
+            NonNull::new_unchecked(footer_ptr as *mut u8)
+        });
+
+        // The `allocated_bytes` of a new chunk counts the total size
+        // of the chunks, not how much of the chunks are used.
+        let allocated_bytes = prev.as_ref().allocated_bytes + size_without_footer;
+
+        ptr::write(
+            footer_ptr,
+            ChunkFooter {
+                data,
+                layout,
+                prev: Cell::new(prev),
+                ptr,
                 allocated_bytes,
             },
         );
 
-        Some(NonNull::new_unchecked(footer_ptr))
+        NonNull::new_unchecked(footer_ptr)
     }
 
     /// Reset this bump allocator.
@@ -759,7 +1489,19 @@ impl Bump {
     pub fn reset(&mut self) {
         // Takes `&mut self` so `self` must be unique and there can't be any
         // borrows active that would get invalidated by resetting.
+        #[cfg(feature = "debug-checks")]
+        {
+            self.assert_integrity();
+            self.red_zones.clear();
+        }
+
         unsafe {
+            self.record_high_water_mark();
+            self.high_water_mark.set(0);
+            self.dealloc_chunk_list(self.spare_chunks.replace(EMPTY_CHUNK.get()));
+            self.spare_bytes.set(0);
+            self.prev_chunks_used_bytes.set(0);
+
             if self.current_chunk_footer.get().as_ref().is_empty() {
                 return;
             }
@@ -768,10 +1510,17 @@ impl Bump {
 
             // Deallocate all chunks except the current one
             let prev_chunk = cur_chunk.as_ref().prev.replace(EMPTY_CHUNK.get());
-            dealloc_chunk_list(prev_chunk);
+            self.dealloc_chunk_list(prev_chunk);
+
+            #[cfg(feature = "debug-checks")]
+            self.fill_freed_chunk(cur_chunk.as_ref());
 
-            // Reset the bump finger to the end of the chunk.
-            cur_chunk.as_ref().ptr.set(cur_chunk.cast());
+            // Reset the bump finger to where allocation starts in the chunk.
+            cur_chunk
+                .as_ref()
+                .ptr
+                .set(Self::chunk_start(cur_chunk.as_ref()));
+            self.poison_free_space(cur_chunk.as_ref());
 
             // Reset the allocated size of the chunk.
             cur_chunk.as_mut().allocated_bytes = cur_chunk.as_ref().layout.size();
@@ -788,12 +1537,320 @@ impl Bump {
             );
             debug_assert_eq!(
                 self.current_chunk_footer.get().as_ref().ptr.get(),
-                self.current_chunk_footer.get().cast(),
+                Self::chunk_start(self.current_chunk_footer.get().as_ref()),
                 "Our chunk's bump finger should be reset to the start of its allocation"
             );
         }
     }
 
+    /// Reset this bump allocator, and choose which of its chunks to keep for
+    /// future allocations.
+    ///
+    /// Like [`reset`][Bump::reset], this deallocates everything that was
+    /// allocated in this arena without running any `Drop` implementations.
+    /// Unlike `reset`, which keeps only the most recently allocated chunk,
+    /// this keeps the chunks that `policy` asks for, and returns the rest to
+    /// the backing allocator. Chunks that are kept still count towards
+    /// [`allocated_bytes`][Bump::allocated_bytes] and the allocation limit.
+    ///
+    /// The kept chunks are bump allocated into again before any new chunk is
+    /// allocated, starting with the oldest one.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{Bump, RetainPolicy};
+    ///
+    /// let mut bump = Bump::new();
+    /// for frame in 0..10 {
+    ///     bump.alloc_slice_fill_copy(10_000, frame);
+    ///
+    ///     // Keep a single chunk that is big enough for a whole frame, so that
+    ///     // the next frame doesn't need to allocate any chunks.
+    ///     bump.reset_with(RetainPolicy::Merge);
+    /// }
+    /// assert!(bump.allocated_bytes() >= 10_000);
+    /// ```
+    pub fn reset_with(&mut self, policy: RetainPolicy) {
+        #[cfg(feature = "debug-checks")]
+        {
+            self.assert_integrity();
+            self.red_zones.clear();
+        }
+
+        unsafe {
+            self.record_high_water_mark();
+            let high_water_mark = self.high_water_mark.replace(0).max(self.used_bytes());
+            self.prev_chunks_used_bytes.set(0);
+
+            // Empty every chunk and move it to the spare chunks. The current
+            // chunk is the newest one, so the oldest one ends up first.
+            let mut footer = self.current_chunk_footer.replace(EMPTY_CHUNK.get());
+            while !footer.as_ref().is_empty() {
+                let f = footer.as_ref();
+                let prev = f.prev.replace(self.spare_chunks.get());
+                #[cfg(feature = "debug-checks")]
+                self.fill_freed_chunk(f);
+                f.ptr.set(Self::chunk_start(f));
+                self.poison_free_space(f);
+                self.spare_chunks.set(footer);
+                footer = prev;
+            }
+
+            match policy {
+                RetainPolicy::All => {}
+                RetainPolicy::UpTo(max_bytes) => {
+                    let mut kept = 0;
+                    let mut link = &self.spare_chunks;
+                    loop {
+                        let footer = link.get();
+                        let f = &*footer.as_ptr();
+                        if f.is_empty() {
+                            break;
+                        }
+                        kept += f.capacity();
+                        if kept > max_bytes {
+                            link.set(EMPTY_CHUNK.get());
+                            self.dealloc_chunk_list(footer);
+                            break;
+                        }
+                        link = &f.prev;
+                    }
+                }
+                RetainPolicy::Merge => {
+                    // Keep the smallest chunk that everything fits in, if
+                    // there is one.
+                    let mut best: Option<&Cell<NonNull<ChunkFooter>>> = None;
+                    let mut link = &self.spare_chunks;
+                    loop {
+                        let f = &*link.get().as_ptr();
+                        if f.is_empty() {
+                            break;
+                        }
+                        let fits = f.capacity() >= high_water_mark;
+                        if fits && best.map_or(true, |b| b.get().as_ref().capacity() > f.capacity())
+                        {
+                            best = Some(link);
+                        }
+                        link = &f.prev;
+                    }
+
+                    let keep = best.map(|link| {
+                        let footer = link.get();
+                        link.set(footer.as_ref().prev.replace(EMPTY_CHUNK.get()));
+                        footer
+                    });
+                    self.dealloc_chunk_list(self.spare_chunks.replace(EMPTY_CHUNK.get()));
+
+                    // Otherwise, allocate a new chunk that is big enough.
+                    let keep = keep.or_else(|| {
+                        if high_water_mark == 0 {
+                            return None;
+                        }
+                        let layout = Layout::new::<u8>();
+                        let details = Self::new_chunk_memory_details(
+                            Some(high_water_mark),
+                            layout,
+                            &self.chunk_policy,
+                        )?;
+                        if !Self::chunk_fits_under_limit(self.allocation_limit_remaining(), details)
+                        {
+                            return None;
+                        }
+                        self.new_budgeted_chunk(details, layout, EMPTY_CHUNK.get(), false)
+                            .ok()
+                    });
+                    if let Some(footer) = keep {
+                        self.spare_chunks.set(footer);
+                    }
+                }
+            }
+
+            // Start bump allocating into the oldest chunk right away.
+            let mut footer = self.spare_chunks.get();
+            if !footer.as_ref().is_empty() {
+                self.spare_chunks
+                    .set(footer.as_ref().prev.replace(EMPTY_CHUNK.get()));
+                footer.as_mut().allocated_bytes = footer.as_ref().capacity();
+                self.current_chunk_footer.set(footer);
+            }
+            self.spare_bytes
+                .set(self.iter_spare_chunks().map(|f| f.capacity()).sum());
+        }
+    }
+
+    /// Record the current allocation state of this arena, so that it can later
+    /// be rolled back to with [`reset_to`][Bump::reset_to].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// bump.alloc(1);
+    ///
+    /// let checkpoint = bump.checkpoint();
+    /// let before = bump.allocated_bytes();
+    /// bump.alloc_slice_fill_copy(10_000, 0_u8);
+    ///
+    /// // Safe because we haven't kept any references to the slice.
+    /// unsafe {
+    ///     bump.reset_to(checkpoint);
+    /// }
+    /// assert_eq!(bump.allocated_bytes(), before);
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint {
+        let footer = self.current_chunk_footer.get();
+        let ptr = unsafe { footer.as_ref().ptr.get() };
+        Checkpoint { footer, ptr }
+    }
+
+    /// Roll this arena back to a previously recorded [`Checkpoint`].
+    ///
+    /// Every allocation made since the checkpoint was recorded is
+    /// deallocated, and any chunks that were allocated since then are returned
+    /// to the backing allocator. Allocations made before the checkpoint are
+    /// left untouched. Does not run any `Drop` implementations on deallocated
+    /// objects; see [the top-level documentation](struct.Bump.html) for
+    /// details.
+    ///
+    /// See [`scope`][Bump::scope] for a safe alternative.
+    ///
+    /// ## Safety
+    ///
+    /// * `checkpoint` must have been created by this arena's
+    ///   [`checkpoint`][Bump::checkpoint] method.
+    ///
+    /// * The arena must not have been [`reset`][Bump::reset], or rolled back
+    ///   to a checkpoint that was recorded before `checkpoint`, since
+    ///   `checkpoint` was recorded.
+    ///
+    /// * There must not be any live references to, or other uses of, values
+    ///   that were allocated since `checkpoint` was recorded.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.alloc(1);
+    ///
+    /// let checkpoint = bump.checkpoint();
+    /// let y = bump.alloc(2);
+    /// assert_eq!(*y, 2);
+    ///
+    /// unsafe {
+    ///     bump.reset_to(checkpoint);
+    /// }
+    ///
+    /// // `x` is still valid, and the space `y` used will be reused.
+    /// assert_eq!(*x, 1);
+    /// ```
+    pub unsafe fn reset_to(&self, checkpoint: Checkpoint) {
+        debug_assert!(
+            self.iter_footers().any(|f| f == checkpoint.footer),
+            "checkpoint's chunk should still be part of this arena"
+        );
+        #[cfg(feature = "debug-checks")]
+        self.assert_integrity();
+
+        // Remember how much was in use before rolling back, for
+        // `RetainPolicy::Merge`.
+        self.high_water_mark
+            .set(self.high_water_mark.get().max(self.used_bytes()));
+        self.record_high_water_mark();
+
+        // Deallocate every chunk that was allocated after the checkpoint.
+        let mut footer = self.current_chunk_footer.get();
+        while footer != checkpoint.footer {
+            let f = footer;
+            footer = f.as_ref().prev.get();
+            self.dealloc_chunk(f);
+            self.prev_chunks_used_bytes
+                .set(self.prev_chunks_used_bytes.get() - footer.as_ref().as_raw_parts(UP).1);
+        }
+        self.current_chunk_footer.set(footer);
+
+        // Rewind the bump finger within the checkpoint's chunk. The canonical
+        // empty chunk is shared and its finger never moves, so don't write
+        // to it.
+        if !footer.as_ref().is_empty() {
+            debug_assert!(footer.as_ref().data <= checkpoint.ptr);
+            debug_assert!(checkpoint.ptr <= footer.cast());
+            let finger = footer.as_ref().ptr.get().as_ptr();
+            let (start, end) = if UP {
+                (checkpoint.ptr.as_ptr(), finger)
+            } else {
+                (finger, checkpoint.ptr.as_ptr())
+            };
+            let len = end as usize - start as usize;
+            #[cfg(feature = "debug-checks")]
+            {
+                self.poisoning.unpoison(start, len);
+                ptr::write_bytes(start, debug_checks::FREED_BYTE, len);
+            }
+            footer.as_ref().ptr.set(checkpoint.ptr);
+            self.poisoning.poison(start, len);
+        }
+
+        #[cfg(feature = "debug-checks")]
+        self.forget_dead_red_zones();
+    }
+
+    /// Run `f` with this arena, then deallocate everything that `f`
+    /// allocated in it.
+    ///
+    /// This is a safe version of recording a [`checkpoint`][Bump::checkpoint]
+    /// and rolling back to it with [`reset_to`][Bump::reset_to]: because this
+    /// method takes `&mut self`, and `f`'s result cannot borrow from the arena
+    /// it is given, no allocations made within `f` can outlive the call.
+    ///
+    /// The arena is rolled back even if `f` panics.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut bump = bumpalo::Bump::new();
+    ///
+    /// let sum = bump.scope(|bump| {
+    ///     let xs = bump.alloc_slice_fill_with(100, |i| i);
+    ///     xs.iter().sum::<usize>()
+    /// });
+    /// assert_eq!(sum, 4950);
+    /// ```
+    ///
+    /// Values allocated inside the scope can't escape it:
+    ///
+    /// ```compile_fail
+    /// let mut bump = bumpalo::Bump::new();
+    /// let x = bump.scope(|bump| bump.alloc(1));
+    /// # let _ = x;
+    /// ```
+    pub fn scope<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&Self) -> R,
+    {
+        struct Rollback<'a, A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> {
+            bump: &'a Bump<A, MIN_ALIGN, UP>,
+            checkpoint: Checkpoint,
+        }
+
+        impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> Drop
+            for Rollback<'_, A, MIN_ALIGN, UP>
+        {
+            fn drop(&mut self) {
+                // SAFETY: The checkpoint was just recorded from this arena, we
+                // have exclusive access to it, and `f`'s result can't borrow
+                // anything allocated within the scope.
+                unsafe { self.bump.reset_to(self.checkpoint) }
+            }
+        }
+
+        let rollback = Rollback {
+            checkpoint: self.checkpoint(),
+            bump: self,
+        };
+        f(rollback.bump)
+    }
+
     /// Allocate an object in this `Bump` and return an exclusive reference to
     /// it.
     ///
@@ -1003,7 +2060,9 @@ impl Bump {
                 // reclaim any alignment padding we might have added (which
                 // `dealloc` cannot do) if we didn't allocate a new chunk for
                 // this result.
-                if self.is_last_allocation(inner_result_ptr.cast()) {
+                if self.is_last_allocation(inner_result_ptr.cast(), mem::size_of::<Result<T, E>>())
+                {
+                    self.record_high_water_mark();
                     let current_footer_p = self.current_chunk_footer.get();
                     let current_ptr = &current_footer_p.as_ref().ptr;
                     if current_footer_p == rewind_footer {
@@ -1028,8 +2087,11 @@ impl Bump {
                         // Because this is the only allocation in this chunk,
                         // we can reset the chunk's bump finger to the start of
                         // the chunk.
-                        current_ptr.set(current_footer_p.as_ref().data);
+                        current_ptr.set(Self::chunk_start(current_footer_p.as_ref()));
                     }
+                    self.poison_free_space(current_footer_p.as_ref());
+                    #[cfg(feature = "debug-checks")]
+                    self.forget_dead_red_zones();
                 }
                 //SAFETY:
                 // As we received `E` semantically by value from `f`, we can
@@ -1111,7 +2173,9 @@ impl Bump {
                 // reclaim any alignment padding we might have added (which
                 // `dealloc` cannot do) if we didn't allocate a new chunk for
                 // this result.
-                if self.is_last_allocation(inner_result_ptr.cast()) {
+                if self.is_last_allocation(inner_result_ptr.cast(), mem::size_of::<Result<T, E>>())
+                {
+                    self.record_high_water_mark();
                     let current_footer_p = self.current_chunk_footer.get();
                     let current_ptr = &current_footer_p.as_ref().ptr;
                     if current_footer_p == rewind_footer {
@@ -1136,8 +2200,11 @@ impl Bump {
                         // Because this is the only allocation in this chunk,
                         // we can reset the chunk's bump finger to the start of
                         // the chunk.
-                        current_ptr.set(current_footer_p.as_ref().data);
+                        current_ptr.set(Self::chunk_start(current_footer_p.as_ref()));
                     }
+                    self.poison_free_space(current_footer_p.as_ref());
+                    #[cfg(feature = "debug-checks")]
+                    self.forget_dead_red_zones();
                 }
                 //SAFETY:
                 // As we received `E` semantically by value from `f`, we can
@@ -1180,6 +2247,34 @@ impl Bump {
         }
     }
 
+    /// Try to `Copy` a slice into this `Bump` and return an exclusive
+    /// reference to the copy.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_copy(&[1, 2, 3]);
+    /// assert_eq!(x, Ok(&mut [1, 2, 3][..]));
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_copy<T>(&self, src: &[T]) -> Result<&mut [T], AllocErr>
+    where
+        T: Copy,
+    {
+        let layout = Layout::for_value(src);
+        let dst = self.try_alloc_layout(layout)?.cast::<T>();
+
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), src.len());
+            Ok(slice::from_raw_parts_mut(dst.as_ptr(), src.len()))
+        }
+    }
+
     /// `Clone` a slice into this `Bump` and return an exclusive reference to
     /// the clone. Prefer [`alloc_slice_copy`](#method.alloc_slice_copy) if `T` is `Copy`.
     ///
@@ -1222,6 +2317,39 @@ impl Bump {
         }
     }
 
+    /// Try to `Clone` a slice into this `Bump` and return an exclusive
+    /// reference to the clone. Prefer
+    /// [`try_alloc_slice_copy`](#method.try_alloc_slice_copy) if `T` is `Copy`.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let originals = [String::from("Alice"), String::from("Bob")];
+    /// let clones = bump.try_alloc_slice_clone(&originals).unwrap();
+    /// assert_eq!(originals, clones);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_clone<T>(&self, src: &[T]) -> Result<&mut [T], AllocErr>
+    where
+        T: Clone,
+    {
+        let layout = Layout::for_value(src);
+        let dst = self.try_alloc_layout(layout)?.cast::<T>();
+
+        unsafe {
+            for (i, val) in src.iter().cloned().enumerate() {
+                ptr::write(dst.as_ptr().add(i), val);
+            }
+
+            Ok(slice::from_raw_parts_mut(dst.as_ptr(), src.len()))
+        }
+    }
+
     /// `Copy` a string slice into this `Bump` and return an exclusive reference to it.
     ///
     /// ## Panics
@@ -1244,102 +2372,506 @@ impl Bump {
         }
     }
 
-    /// Allocates a new slice of size `len` into this `Bump` and returns an
-    /// exclusive reference to the copy.
-    ///
-    /// The elements of the slice are initialized using the supplied closure.
-    /// The closure argument is the position in the slice.
+    /// Try to `Copy` a string slice into this `Bump` and return an exclusive
+    /// reference to it.
     ///
-    /// ## Panics
+    /// ## Errors
     ///
-    /// Panics if reserving space for the slice fails.
+    /// Errors if reserving space for the string fails.
     ///
     /// ## Example
     ///
     /// ```
     /// let bump = bumpalo::Bump::new();
-    /// let x = bump.alloc_slice_fill_with(5, |i| 5 * (i + 1));
-    /// assert_eq!(x, &[5, 10, 15, 20, 25]);
+    /// let hello = bump.try_alloc_str("hello world").unwrap();
+    /// assert_eq!("hello world", hello);
     /// ```
     #[inline(always)]
-    pub fn alloc_slice_fill_with<T, F>(&self, len: usize, mut f: F) -> &mut [T]
-    where
-        F: FnMut(usize) -> T,
-    {
-        let layout = Layout::array::<T>(len).unwrap_or_else(|_| oom());
-        let dst = self.alloc_layout(layout).cast::<T>();
-
+    pub fn try_alloc_str(&self, src: &str) -> Result<&mut str, AllocErr> {
+        let buffer = self.try_alloc_slice_copy(src.as_bytes())?;
         unsafe {
-            for i in 0..len {
-                ptr::write(dst.as_ptr().add(i), f(i));
-            }
+            // This is OK, because it already came in as str, so it is guaranteed to be utf8
+            Ok(str::from_utf8_unchecked_mut(buffer))
+        }
+    }
 
-            let result = slice::from_raw_parts_mut(dst.as_ptr(), len);
-            debug_assert_eq!(Layout::for_value(result), layout);
-            result
+    /// Copy a C string into this `Bump` and return a reference to the copy.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the string fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::ffi::CStr;
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let hello = CStr::from_bytes_with_nul(b"hello\0").unwrap();
+    /// assert_eq!(bump.alloc_c_str(hello), hello);
+    /// ```
+    #[inline]
+    pub fn alloc_c_str(&self, src: &CStr) -> &CStr {
+        let buffer = self.alloc_slice_copy(src.to_bytes_with_nul());
+        unsafe {
+            // This is OK, because it was copied from a `CStr`.
+            CStr::from_bytes_with_nul_unchecked(buffer)
         }
     }
 
-    /// Allocates a new slice of size `len` into this `Bump` and returns an
-    /// exclusive reference to the copy.
+    /// Copy a string slice into this `Bump` as a C string, with a nul
+    /// terminator after it, and return a reference to it.
     ///
-    /// All elements of the slice are initialized to `value`.
+    /// ## Errors
+    ///
+    /// Errors if the string contains a nul byte, in which case nothing is
+    /// allocated.
     ///
     /// ## Panics
     ///
-    /// Panics if reserving space for the slice fails.
+    /// Panics if reserving space for the C string fails.
     ///
     /// ## Example
     ///
     /// ```
     /// let bump = bumpalo::Bump::new();
-    /// let x = bump.alloc_slice_fill_copy(5, 42);
-    /// assert_eq!(x, &[42, 42, 42, 42, 42]);
+    /// let hello = bump.alloc_str_as_c_str("hello").unwrap();
+    /// assert_eq!(hello.to_bytes_with_nul(), b"hello\0");
+    ///
+    /// assert!(bump.alloc_str_as_c_str("hello\0").is_err());
     /// ```
-    #[inline(always)]
-    pub fn alloc_slice_fill_copy<T: Copy>(&self, len: usize, value: T) -> &mut [T] {
-        self.alloc_slice_fill_with(len, |_| value)
+    #[inline]
+    pub fn alloc_str_as_c_str(&self, src: &str) -> Result<&CStr, NulError> {
+        NulError::check(src.as_bytes())?;
+        let len = src.len() + 1;
+        let layout = Layout::array::<u8>(len).unwrap_or_else(|_| oom());
+        let dst = self.alloc_layout(layout);
+
+        unsafe {
+            ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), src.len());
+            ptr::write(dst.as_ptr().add(src.len()), 0);
+            let buffer = slice::from_raw_parts(dst.as_ptr(), len);
+            // This is OK, because there is exactly one nul byte, at the end.
+            Ok(CStr::from_bytes_with_nul_unchecked(buffer))
+        }
     }
 
-    /// Allocates a new slice of size `len` slice into this `Bump` and return an
-    /// exclusive reference to the copy.
+    /// Copy an OS string slice into this `Bump` and return a reference to the
+    /// copy.
     ///
-    /// All elements of the slice are initialized to `value.clone()`.
+    /// This method is only available with the `std` cargo feature, on Unix
+    /// platforms, where OS strings are plain bytes.
     ///
     /// ## Panics
     ///
-    /// Panics if reserving space for the slice fails.
+    /// Panics if reserving space for the string fails.
     ///
     /// ## Example
     ///
     /// ```
+    /// use std::ffi::OsStr;
+    ///
     /// let bump = bumpalo::Bump::new();
-    /// let s: String = "Hello Bump!".to_string();
-    /// let x: &[String] = bump.alloc_slice_fill_clone(2, &s);
-    /// assert_eq!(x.len(), 2);
-    /// assert_eq!(&x[0], &s);
-    /// assert_eq!(&x[1], &s);
+    /// let name = bump.alloc_os_str(OsStr::new("file.txt"));
+    /// assert_eq!(name, "file.txt");
     /// ```
-    #[inline(always)]
-    pub fn alloc_slice_fill_clone<T: Clone>(&self, len: usize, value: &T) -> &mut [T] {
-        self.alloc_slice_fill_with(len, |_| value.clone())
+    #[cfg(all(feature = "std", unix))]
+    #[inline]
+    pub fn alloc_os_str(&self, src: &std::ffi::OsStr) -> &std::ffi::OsStr {
+        use std::os::unix::ffi::OsStrExt;
+        std::ffi::OsStr::from_bytes(self.alloc_slice_copy(src.as_bytes()))
     }
 
-    /// Allocates a new slice of size `len` slice into this `Bump` and return an
-    /// exclusive reference to the copy.
+    /// Copy a path into this `Bump` and return a reference to the copy.
     ///
-    /// The elements are initialized using the supplied iterator.
+    /// This method is only available with the `std` cargo feature, on Unix
+    /// platforms, where OS strings are plain bytes.
     ///
     /// ## Panics
     ///
-    /// Panics if reserving space for the slice fails, or if the supplied
-    /// iterator returns fewer elements than it promised.
+    /// Panics if reserving space for the path fails.
     ///
     /// ## Example
     ///
     /// ```
+    /// use std::path::Path;
+    ///
     /// let bump = bumpalo::Bump::new();
-    /// let x: &[i32] = bump.alloc_slice_fill_iter([2, 3, 5].iter().cloned().map(|i| i * i));
+    /// let path = bump.alloc_path(Path::new("/tmp/file.txt"));
+    /// assert_eq!(path.file_name().unwrap(), "file.txt");
+    /// ```
+    #[cfg(all(feature = "std", unix))]
+    #[inline]
+    pub fn alloc_path(&self, src: &std::path::Path) -> &std::path::Path {
+        std::path::Path::new(self.alloc_os_str(src.as_os_str()))
+    }
+
+    /// Formats the given arguments into a string in this `Bump` and returns
+    /// an exclusive reference to it.
+    ///
+    /// The string is written at the end of the current chunk, and its
+    /// capacity is doubled whenever it runs out of room. In an
+    /// [upward-bumping](#method.upward) `Bump` it grows in place, while in a
+    /// downward-bumping one its bytes are moved down into the newly claimed
+    /// space each time it grows. Either way, unlike with
+    /// [`bumpalo::format!`](macro.format.html), no buffers are left behind
+    /// when it outgrows them, unless the chunk runs out of space and the
+    /// string is copied to a new chunk. Because the capacity doubles, the
+    /// total work of moving and copying the bytes is linear in their number.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the string fails, or if a formatting
+    /// trait implementation returns an error.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let name = "world";
+    /// let s = bump.alloc_fmt(format_args!("hello {name}, {}!", 42));
+    /// assert_eq!(s, "hello world, 42!");
+    /// ```
+    #[inline]
+    pub fn alloc_fmt(&self, args: fmt::Arguments<'_>) -> &mut str {
+        // Only sizes that overflow fail when the allocations themselves can't.
+        self.alloc_fmt_impl(args, true).unwrap_or_else(|_| oom())
+    }
+
+    /// Tries to format the given arguments into a string in this `Bump` and
+    /// returns an exclusive reference to it.
+    ///
+    /// See [`alloc_fmt`](#method.alloc_fmt) for how the string grows.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if a formatting trait implementation returns an error.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the string fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let s = bump.try_alloc_fmt(format_args!("{:>5}", 1)).unwrap();
+    /// assert_eq!(s, "    1");
+    /// ```
+    #[inline]
+    pub fn try_alloc_fmt(&self, args: fmt::Arguments<'_>) -> Result<&mut str, AllocErr> {
+        self.alloc_fmt_impl(args, false)
+    }
+
+    // Format `args` into a new string. If `infallible`, running out of memory
+    // is handled like in `alloc_layout`.
+    fn alloc_fmt_impl(
+        &self,
+        args: fmt::Arguments<'_>,
+        infallible: bool,
+    ) -> Result<&mut str, AllocErr> {
+        if let Some(s) = args.as_str() {
+            return if infallible {
+                Ok(self.alloc_str(s))
+            } else {
+                self.try_alloc_str(s)
+            };
+        }
+
+        let layout = Layout::new::<[u8; 0]>();
+        let ptr = if infallible {
+            self.alloc_layout(layout)
+        } else {
+            self.try_alloc_layout(layout)?
+        };
+        let mut writer = StrWriter {
+            bump: self,
+            ptr,
+            len: 0,
+            cap: 0,
+            infallible,
+            error: None,
+        };
+        if fmt::Write::write_fmt(&mut writer, args).is_err() {
+            let StrWriter {
+                ptr, cap, error, ..
+            } = writer;
+            unsafe { self.dealloc(ptr, Layout::array::<u8>(cap).unwrap()) };
+            return match error {
+                Some(e) => Err(e),
+                None => panic!("a formatting trait implementation returned an error"),
+            };
+        }
+
+        unsafe {
+            // Give back the space that wasn't needed.
+            let StrWriter { ptr, len, cap, .. } = writer;
+            let ptr = self
+                .shrink(
+                    ptr,
+                    Layout::array::<u8>(cap).unwrap(),
+                    Layout::array::<u8>(len).unwrap(),
+                )
+                .unwrap_or(ptr);
+            let bytes = slice::from_raw_parts_mut(ptr.as_ptr(), len);
+            // This is OK, because only `str`s were written into it.
+            Ok(str::from_utf8_unchecked_mut(bytes))
+        }
+    }
+
+    /// Allocates a new slice of size `len` into this `Bump` and returns an
+    /// exclusive reference to the copy.
+    ///
+    /// The elements of the slice are initialized using the supplied closure.
+    /// The closure argument is the position in the slice.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.alloc_slice_fill_with(5, |i| 5 * (i + 1));
+    /// assert_eq!(x, &[5, 10, 15, 20, 25]);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_slice_fill_with<T, F>(&self, len: usize, mut f: F) -> &mut [T]
+    where
+        F: FnMut(usize) -> T,
+    {
+        let layout = Layout::array::<T>(len).unwrap_or_else(|_| oom());
+        let dst = self.alloc_layout(layout).cast::<T>();
+
+        unsafe {
+            for i in 0..len {
+                ptr::write(dst.as_ptr().add(i), f(i));
+            }
+
+            let result = slice::from_raw_parts_mut(dst.as_ptr(), len);
+            debug_assert_eq!(Layout::for_value(result), layout);
+            result
+        }
+    }
+
+    /// Tries to allocate a new slice of size `len` into this `Bump` and
+    /// returns an exclusive reference to the copy.
+    ///
+    /// The elements of the slice are initialized using the supplied closure.
+    /// The closure argument is the position in the slice.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_with(5, |i| 5 * (i + 1)).unwrap();
+    /// assert_eq!(x, &[5, 10, 15, 20, 25]);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_fill_with<T, F>(
+        &self,
+        len: usize,
+        mut f: F,
+    ) -> Result<&mut [T], AllocErr>
+    where
+        F: FnMut(usize) -> T,
+    {
+        let layout = Layout::array::<T>(len).map_err(|_| AllocErr::size_overflow())?;
+        let dst = self.try_alloc_layout(layout)?.cast::<T>();
+
+        unsafe {
+            for i in 0..len {
+                ptr::write(dst.as_ptr().add(i), f(i));
+            }
+
+            let result = slice::from_raw_parts_mut(dst.as_ptr(), len);
+            debug_assert_eq!(Layout::for_value(result), layout);
+            Ok(result)
+        }
+    }
+
+    /// Tries to allocate a new slice of size `len` into this `Bump`, and to
+    /// initialize its elements with a closure that may fail, then returns an
+    /// exclusive reference to the slice.
+    ///
+    /// The closure argument is the position in the slice. If the closure
+    /// returns an error, the elements that were already initialized are
+    /// dropped, the slice is deallocated if it is still the most recent
+    /// allocation, and the error is returned.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails, or if the closure fails
+    /// to initialize an element.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::AllocOrInitError;
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_with_result(3, |i| Ok::<_, ()>(i * 2));
+    /// assert_eq!(x.unwrap(), &[0, 2, 4]);
+    ///
+    /// let parsed = bump.try_alloc_slice_fill_with_result(3, |i| ["1", "2", "x"][i].parse::<u32>());
+    /// assert!(matches!(parsed, Err(AllocOrInitError::Init(_))));
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_fill_with_result<T, E, F>(
+        &self,
+        len: usize,
+        mut f: F,
+    ) -> Result<&mut [T], AllocOrInitError<E>>
+    where
+        F: FnMut(usize) -> Result<T, E>,
+    {
+        let layout = Layout::array::<T>(len).map_err(|_| AllocErr::size_overflow())?;
+        let dst = self.try_alloc_layout(layout)?.cast::<T>();
+
+        // Drop the elements that were initialized so far if the closure fails
+        // or panics.
+        let mut initialized = InitializedPrefix {
+            dst: dst.as_ptr(),
+            len: 0,
+        };
+        unsafe {
+            for i in 0..len {
+                match f(i) {
+                    Ok(value) => {
+                        ptr::write(dst.as_ptr().add(i), value);
+                        initialized.len += 1;
+                    }
+                    Err(e) => {
+                        drop(initialized);
+                        self.dealloc(dst.cast(), layout);
+                        return Err(AllocOrInitError::Init(e));
+                    }
+                }
+            }
+            mem::forget(initialized);
+
+            let result = slice::from_raw_parts_mut(dst.as_ptr(), len);
+            debug_assert_eq!(Layout::for_value(result), layout);
+            Ok(result)
+        }
+    }
+
+    /// Allocates a new slice of size `len` into this `Bump` and returns an
+    /// exclusive reference to the copy.
+    ///
+    /// All elements of the slice are initialized to `value`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.alloc_slice_fill_copy(5, 42);
+    /// assert_eq!(x, &[42, 42, 42, 42, 42]);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_slice_fill_copy<T: Copy>(&self, len: usize, value: T) -> &mut [T] {
+        self.alloc_slice_fill_with(len, |_| value)
+    }
+
+    /// Tries to allocate a new slice of size `len` into this `Bump` and
+    /// returns an exclusive reference to the copy.
+    ///
+    /// All elements of the slice are initialized to `value`.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_copy(5, 42).unwrap();
+    /// assert_eq!(x, &[42, 42, 42, 42, 42]);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_fill_copy<T: Copy>(
+        &self,
+        len: usize,
+        value: T,
+    ) -> Result<&mut [T], AllocErr> {
+        self.try_alloc_slice_fill_with(len, |_| value)
+    }
+
+    /// Allocates a new slice of size `len` slice into this `Bump` and return an
+    /// exclusive reference to the copy.
+    ///
+    /// All elements of the slice are initialized to `value.clone()`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let s: String = "Hello Bump!".to_string();
+    /// let x: &[String] = bump.alloc_slice_fill_clone(2, &s);
+    /// assert_eq!(x.len(), 2);
+    /// assert_eq!(&x[0], &s);
+    /// assert_eq!(&x[1], &s);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_slice_fill_clone<T: Clone>(&self, len: usize, value: &T) -> &mut [T] {
+        self.alloc_slice_fill_with(len, |_| value.clone())
+    }
+
+    /// Tries to allocate a new slice of size `len` into this `Bump` and
+    /// returns an exclusive reference to the copy.
+    ///
+    /// All elements of the slice are initialized to `value.clone()`.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let s: String = "Hello Bump!".to_string();
+    /// let x: &[String] = bump.try_alloc_slice_fill_clone(2, &s).unwrap();
+    /// assert_eq!(x, [s.clone(), s]);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_fill_clone<T: Clone>(
+        &self,
+        len: usize,
+        value: &T,
+    ) -> Result<&mut [T], AllocErr> {
+        self.try_alloc_slice_fill_with(len, |_| value.clone())
+    }
+
+    /// Allocates a new slice of size `len` slice into this `Bump` and return an
+    /// exclusive reference to the copy.
+    ///
+    /// The elements are initialized using the supplied iterator.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails, or if the supplied
+    /// iterator returns fewer elements than it promised.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x: &[i32] = bump.alloc_slice_fill_iter([2, 3, 5].iter().cloned().map(|i| i * i));
     /// assert_eq!(x, [4, 9, 25]);
     /// ```
     #[inline(always)]
@@ -1354,27 +2886,444 @@ impl Bump {
         })
     }
 
-    /// Allocates a new slice of size `len` slice into this `Bump` and return an
-    /// exclusive reference to the copy.
-    ///
-    /// All elements of the slice are initialized to [`T::default()`].
+    /// Tries to allocate a new slice of size `len` into this `Bump` and
+    /// returns an exclusive reference to the copy.
+    ///
+    /// The elements are initialized using the supplied iterator.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the supplied iterator returns fewer elements than it
+    /// promised.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_iter([2, 3, 5].iter().map(|i| i * i));
+    /// assert_eq!(x.unwrap(), [4, 9, 25]);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_fill_iter<T, I>(&self, iter: I) -> Result<&mut [T], AllocErr>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let mut iter = iter.into_iter();
+        self.try_alloc_slice_fill_with(iter.len(), |_| {
+            iter.next().expect("Iterator supplied too few elements")
+        })
+    }
+
+    /// Allocates a new slice in this `Bump`, fills it with the elements of the
+    /// given iterator, and returns an exclusive reference to it.
+    ///
+    /// Unlike [`alloc_slice_fill_iter`](#method.alloc_slice_fill_iter), the
+    /// iterator doesn't need to know its exact length. Space is reserved
+    /// according to its [`size_hint`], and doubled whenever the slice runs
+    /// out of room. While the slice is the last allocation in the arena, the
+    /// space it grows into is taken from the current chunk without leaving
+    /// its old space behind: in an [upward-bumping](#method.upward) `Bump`
+    /// it grows in place, while in a downward-bumping one its elements are
+    /// moved down into the newly claimed space each time it grows. It is
+    /// copied to a new place when it outgrows the current chunk, or when the
+    /// iterator allocates in this `Bump` itself. Because the capacity doubles,
+    /// the total work of moving and copying the elements is linear in their
+    /// number. Unused space at the end is given back once the iterator is
+    /// exhausted.
+    ///
+    /// [`size_hint`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.size_hint
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let evens = bump.alloc_slice_from_iter((1..10).filter(|i| i % 2 == 0));
+    /// assert_eq!(evens, [2, 4, 6, 8]);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_slice_from_iter<T, I>(&self, iter: I) -> &mut [T]
+    where
+        I: IntoIterator<Item = T>,
+    {
+        // Only sizes that overflow fail when the allocations themselves can't.
+        self.alloc_slice_from_iter_impl(iter, true)
+            .unwrap_or_else(|_| oom())
+    }
+
+    /// Tries to allocate a new slice in this `Bump`, fills it with the
+    /// elements of the given iterator, and returns an exclusive reference to
+    /// it.
+    ///
+    /// See [`alloc_slice_from_iter`](#method.alloc_slice_from_iter) for how
+    /// the slice grows.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails. The elements that the
+    /// iterator produced so far are dropped.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let words = bump.try_alloc_slice_from_iter("a bc d".split(' ').flat_map(str::chars));
+    /// assert_eq!(words.unwrap(), ['a', 'b', 'c', 'd']);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_from_iter<T, I>(&self, iter: I) -> Result<&mut [T], AllocErr>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.alloc_slice_from_iter_impl(iter, false)
+    }
+
+    // Collect `iter` into a new slice. If `infallible`, running out of memory
+    // is handled like in `alloc_layout`.
+    fn alloc_slice_from_iter_impl<T, I>(
+        &self,
+        iter: I,
+        infallible: bool,
+    ) -> Result<&mut [T], AllocErr>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iter.into_iter();
+        let mut cap = if mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            iter.size_hint().0
+        };
+        let mut layout = Layout::array::<T>(cap).map_err(|_| AllocErr::size_overflow())?;
+        let dst = if infallible {
+            self.alloc_layout(layout)
+        } else {
+            self.try_alloc_layout(layout)?
+        };
+
+        // Drop the elements that were initialized so far if the iterator
+        // panics or growing the slice fails.
+        let mut initialized = InitializedPrefix {
+            dst: dst.cast::<T>().as_ptr(),
+            len: 0,
+        };
+        while let Some(value) = iter.next() {
+            if initialized.len == cap {
+                // Doubling the capacity keeps the total work of moving the
+                // slice down, or copying it to a new chunk, linear in its
+                // final length.
+                let len = initialized.len;
+                cap = cap
+                    .saturating_mul(2)
+                    .max(len.saturating_add(1).saturating_add(iter.size_hint().0))
+                    .max(4);
+                let new_layout = Layout::array::<T>(cap).map_err(|_| AllocErr::size_overflow())?;
+                let old = unsafe { NonNull::new_unchecked(initialized.dst).cast::<u8>() };
+                let grown = match unsafe { self.grow(old, layout, new_layout) } {
+                    Ok(p) => p,
+                    Err(_) if infallible => unsafe {
+                        let p = self.handle_oom(new_layout);
+                        ptr::copy_nonoverlapping(old.as_ptr(), p.as_ptr(), layout.size());
+                        p
+                    },
+                    Err(e) => {
+                        drop(initialized);
+                        unsafe { self.dealloc(old, layout) };
+                        return Err(e);
+                    }
+                };
+                initialized.dst = grown.cast::<T>().as_ptr();
+                layout = new_layout;
+            }
+            unsafe { ptr::write(initialized.dst.add(initialized.len), value) };
+            initialized.len += 1;
+        }
+
+        let (dst, len) = (initialized.dst, initialized.len);
+        mem::forget(initialized);
+        unsafe {
+            // Give back the space that wasn't needed.
+            let dst = NonNull::new_unchecked(dst).cast::<u8>();
+            let dst = match Layout::array::<T>(len) {
+                Ok(new_layout) if new_layout.size() < layout.size() => {
+                    self.shrink(dst, layout, new_layout).unwrap_or(dst)
+                }
+                _ => dst,
+            };
+            Ok(slice::from_raw_parts_mut(dst.cast::<T>().as_ptr(), len))
+        }
+    }
+
+    /// Allocates a new slice of size `len` slice into this `Bump` and return an
+    /// exclusive reference to the copy.
+    ///
+    /// All elements of the slice are initialized to [`T::default()`].
+    ///
+    /// [`T::default()`]: https://doc.rust-lang.org/std/default/trait.Default.html#tymethod.default
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.alloc_slice_fill_default::<u32>(5);
+    /// assert_eq!(x, &[0, 0, 0, 0, 0]);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_slice_fill_default<T: Default>(&self, len: usize) -> &mut [T] {
+        self.alloc_slice_fill_with(len, |_| T::default())
+    }
+
+    /// Tries to allocate a new slice of size `len` into this `Bump` and
+    /// returns an exclusive reference to the copy.
+    ///
+    /// All elements of the slice are initialized to [`T::default()`].
+    ///
+    /// [`T::default()`]: https://doc.rust-lang.org/std/default/trait.Default.html#tymethod.default
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_default::<u32>(5).unwrap();
+    /// assert_eq!(x, &[0, 0, 0, 0, 0]);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_slice_fill_default<T: Default>(
+        &self,
+        len: usize,
+    ) -> Result<&mut [T], AllocErr> {
+        self.try_alloc_slice_fill_with(len, |_| T::default())
+    }
+
+    /// Allocates space for a `T` in this `Bump`, without initializing it, and
+    /// returns an exclusive reference to it.
+    ///
+    /// This avoids writing the memory twice when it is initialized in place
+    /// later, which matters for large values.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.alloc_uninit::<[u64; 4]>();
+    /// let x: &mut [u64; 4] = x.write([1, 2, 3, 4]);
+    /// assert_eq!(x, &[1, 2, 3, 4]);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_uninit<T>(&self) -> &mut mem::MaybeUninit<T> {
+        let layout = Layout::new::<T>();
+        let p = self.alloc_layout(layout);
+        unsafe { &mut *p.as_ptr().cast::<mem::MaybeUninit<T>>() }
+    }
+
+    /// Tries to allocate space for a `T` in this `Bump`, without initializing
+    /// it, and returns an exclusive reference to it.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_uninit::<u64>().unwrap();
+    /// assert_eq!(*x.write(42), 42);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_uninit<T>(&self) -> Result<&mut mem::MaybeUninit<T>, AllocErr> {
+        let layout = Layout::new::<T>();
+        let p = self.try_alloc_layout(layout)?;
+        Ok(unsafe { &mut *p.as_ptr().cast::<mem::MaybeUninit<T>>() })
+    }
+
+    /// Allocates space for a slice of `len` values of type `T` in this `Bump`,
+    /// without initializing them, and returns an exclusive reference to it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let xs = bump.alloc_uninit_slice::<u32>(3);
+    /// for (i, x) in xs.iter_mut().enumerate() {
+    ///     x.write(i as u32 * 10);
+    /// }
+    /// let xs = unsafe { &*(xs as *const [_] as *const [u32]) };
+    /// assert_eq!(xs, &[0, 10, 20]);
+    /// ```
+    #[inline(always)]
+    pub fn alloc_uninit_slice<T>(&self, len: usize) -> &mut [mem::MaybeUninit<T>] {
+        let layout = Layout::array::<T>(len).unwrap_or_else(|_| oom());
+        let p = self.alloc_layout(layout);
+        unsafe { slice::from_raw_parts_mut(p.as_ptr().cast(), len) }
+    }
+
+    /// Tries to allocate space for a slice of `len` values of type `T` in this
+    /// `Bump`, without initializing them, and returns an exclusive reference
+    /// to it.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let xs = bump.try_alloc_uninit_slice::<u32>(3).unwrap();
+    /// assert_eq!(xs.len(), 3);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_uninit_slice<T>(
+        &self,
+        len: usize,
+    ) -> Result<&mut [mem::MaybeUninit<T>], AllocErr> {
+        let layout = Layout::array::<T>(len).map_err(|_| AllocErr::size_overflow())?;
+        let p = self.try_alloc_layout(layout)?;
+        Ok(unsafe { slice::from_raw_parts_mut(p.as_ptr().cast(), len) })
+    }
+
+    /// Allocates a slice of `len` zeroed values of type `T` in this `Bump`,
+    /// and returns an exclusive reference to it.
+    ///
+    /// When the slice doesn't fit in the current chunk, the new chunk is
+    /// requested zeroed from the backing allocator, which can often provide
+    /// zeroed memory without writing it, so the slice isn't zeroed again.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let xs = bump.alloc_zeroed_slice::<f64>(1 << 20);
+    /// assert!(xs.iter().all(|&x| x == 0.0));
+    /// ```
+    #[inline(always)]
+    pub fn alloc_zeroed_slice<T: Zeroable>(&self, len: usize) -> &mut [T] {
+        let layout = Layout::array::<T>(len).unwrap_or_else(|_| oom());
+        let p = self.alloc_layout_zeroed(layout);
+        unsafe { slice::from_raw_parts_mut(p.as_ptr().cast(), len) }
+    }
+
+    /// Tries to allocate a slice of `len` zeroed values of type `T` in this
+    /// `Bump`, and returns an exclusive reference to it.
+    ///
+    /// When the slice doesn't fit in the current chunk, the new chunk is
+    /// requested zeroed from the backing allocator.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if reserving space for the slice fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::Bump::new();
+    /// let xs = bump.try_alloc_zeroed_slice::<u8>(100).unwrap();
+    /// assert_eq!(xs, &[0; 100][..]);
+    /// ```
+    #[inline(always)]
+    pub fn try_alloc_zeroed_slice<T: Zeroable>(&self, len: usize) -> Result<&mut [T], AllocErr> {
+        let layout = Layout::array::<T>(len).map_err(|_| AllocErr::size_overflow())?;
+        let p = self.try_alloc_layout_zeroed(layout)?;
+        Ok(unsafe { slice::from_raw_parts_mut(p.as_ptr().cast(), len) })
+    }
+
+    /// Allocates space for a `T` in this `Bump` and initializes it in place
+    /// with the given closure, then returns an exclusive reference to it.
+    ///
+    /// The closure gets the uninitialized slot for the value, and returns a
+    /// reference to the value once it initialized it, typically from
+    /// [`MaybeUninit::write`] or [`MaybeUninit::assume_init_mut`]. Unlike with
+    /// [`alloc_with`](#method.alloc_with), the value is never moved, so large
+    /// structs can be built without ever being on the stack.
+    ///
+    /// [`MaybeUninit::write`]: https://doc.rust-lang.org/std/mem/union.MaybeUninit.html#method.write
+    /// [`MaybeUninit::assume_init_mut`]: https://doc.rust-lang.org/std/mem/union.MaybeUninit.html#method.assume_init_mut
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::ptr::addr_of_mut;
+    ///
+    /// struct Big {
+    ///     len: usize,
+    ///     data: [u8; 1 << 20],
+    /// }
+    ///
+    /// let bump = bumpalo::Bump::new();
+    /// let big = bump.alloc_init(|slot: &mut std::mem::MaybeUninit<Big>| unsafe {
+    ///     let p = slot.as_mut_ptr();
+    ///     addr_of_mut!((*p).len).write(3);
+    ///     addr_of_mut!((*p).data).cast::<u8>().write_bytes(7, 1 << 20);
+    ///     slot.assume_init_mut()
+    /// });
+    /// assert_eq!(big.len, 3);
+    /// assert!(big.data.iter().all(|&b| b == 7));
+    /// ```
+    #[inline(always)]
+    pub fn alloc_init<T, F>(&self, f: F) -> &mut T
+    where
+        F: for<'a> FnOnce(&'a mut mem::MaybeUninit<T>) -> &'a mut T,
+    {
+        f(self.alloc_uninit())
+    }
+
+    /// Tries to allocate space for a `T` in this `Bump` and initializes it in
+    /// place with the given closure, then returns an exclusive reference to
+    /// it.
     ///
-    /// [`T::default()`]: https://doc.rust-lang.org/std/default/trait.Default.html#tymethod.default
+    /// See [`alloc_init`](#method.alloc_init) for how the closure initializes
+    /// the value. It is only called if the allocation succeeds.
     ///
-    /// ## Panics
+    /// ## Errors
     ///
-    /// Panics if reserving space for the slice fails.
+    /// Errors if reserving space for `T` fails.
     ///
     /// ## Example
     ///
     /// ```
     /// let bump = bumpalo::Bump::new();
-    /// let x = bump.alloc_slice_fill_default::<u32>(5);
-    /// assert_eq!(x, &[0, 0, 0, 0, 0]);
+    /// let x = bump.try_alloc_init(|slot| slot.write([1_u32; 64])).unwrap();
+    /// assert_eq!(x[63], 1);
     /// ```
     #[inline(always)]
-    pub fn alloc_slice_fill_default<T: Default>(&self, len: usize) -> &mut [T] {
-        self.alloc_slice_fill_with(len, |_| T::default())
+    pub fn try_alloc_init<T, F>(&self, f: F) -> Result<&mut T, AllocErr>
+    where
+        F: for<'a> FnOnce(&'a mut mem::MaybeUninit<T>) -> &'a mut T,
+    {
+        Ok(f(self.try_alloc_uninit()?))
     }
 
     /// Allocate space for an object with the given `Layout`.
@@ -1388,7 +3337,26 @@ impl Bump {
     /// Panics if reserving space matching `layout` fails.
     #[inline(always)]
     pub fn alloc_layout(&self, layout: Layout) -> NonNull<u8> {
-        self.try_alloc_layout(layout).unwrap_or_else(|_| oom())
+        self.try_alloc_layout(layout)
+            .unwrap_or_else(|_| self.handle_oom(layout))
+    }
+
+    /// Ask the out-of-memory handler what to do about failing to allocate
+    /// `layout`, until it gives up or retrying succeeds.
+    #[inline(never)]
+    #[cold]
+    pub(crate) fn handle_oom(&self, layout: Layout) -> NonNull<u8> {
+        loop {
+            match (self.oom_handler.get())(self, layout) {
+                OomAction::Retry => {
+                    if let Ok(p) = self.try_alloc_layout(layout) {
+                        return p;
+                    }
+                }
+                OomAction::Panic => oom(),
+                OomAction::Abort => abort(),
+            }
+        }
     }
 
     /// Attempts to allocate space for an object with the given `Layout` or else returns
@@ -1403,11 +3371,62 @@ impl Bump {
     /// Errors if reserving space matching `layout` fails.
     #[inline(always)]
     pub fn try_alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
-        if let Some(p) = self.try_alloc_layout_fast(layout) {
-            Ok(p)
+        self.try_alloc_layout_impl(layout, false)
+    }
+
+    // Like `alloc_layout`, but the allocated memory is zeroed.
+    #[inline(always)]
+    fn alloc_layout_zeroed(&self, layout: Layout) -> NonNull<u8> {
+        self.try_alloc_layout_zeroed(layout).unwrap_or_else(|_| {
+            let p = self.handle_oom(layout);
+            unsafe { ptr::write_bytes(p.as_ptr(), 0, layout.size()) };
+            p
+        })
+    }
+
+    // Like `try_alloc_layout`, but the allocated memory is zeroed. New chunks
+    // are requested zeroed from the backing allocator, so allocations that
+    // need one don't have to be zeroed again.
+    #[inline(always)]
+    fn try_alloc_layout_zeroed(&self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        self.try_alloc_layout_impl(layout, true)
+    }
+
+    #[inline(always)]
+    fn try_alloc_layout_impl(&self, layout: Layout, zeroed: bool) -> Result<NonNull<u8>, AllocErr> {
+        #[cfg(feature = "stats")]
+        let before = self.checkpoint();
+
+        let requested = layout;
+        #[cfg(feature = "debug-checks")]
+        let (layout, red_zone_offset) = self.guarded_layout(requested)?;
+        #[cfg(feature = "debug-checks")]
+        let finger = self.checkpoint();
+
+        let (p, is_zeroed) = if let Some(p) = self.try_alloc_layout_fast(layout) {
+            (p, false)
         } else {
-            self.alloc_layout_slow(layout).ok_or(AllocErr)
+            self.alloc_layout_slow(layout, zeroed).map_err(|kind| {
+                let headroom = self
+                    .allocation_limit()
+                    .map(|limit| limit.saturating_sub(self.allocated_bytes()));
+                AllocErr::new(kind, Some(requested), headroom)
+            })?
+        };
+        self.poisoning.unpoison(p.as_ptr(), layout.size());
+
+        #[cfg(feature = "debug-checks")]
+        let p = unsafe { self.guard_allocation(p, red_zone_offset, requested, finger) };
+
+        // The fill pattern of `debug-checks` overwrites fresh zeroed memory.
+        if zeroed && !(is_zeroed && cfg!(not(feature = "debug-checks"))) {
+            unsafe { ptr::write_bytes(p.as_ptr(), 0, requested.size()) };
         }
+
+        #[cfg(feature = "stats")]
+        self.record_allocation(true, requested.size(), before);
+
+        Ok(p)
     }
 
     #[inline(always)]
@@ -1424,12 +3443,27 @@ impl Bump {
             debug_assert!(start <= ptr);
             debug_assert!(ptr as *const u8 <= footer as *const _ as *const u8);
 
+            debug_assert!(is_pointer_aligned_to(ptr, MIN_ALIGN));
+
+            if UP {
+                return self.try_alloc_layout_fast_upward(footer, layout);
+            }
+
             if (ptr as usize) < layout.size() {
                 return None;
             }
 
-            let ptr = ptr.wrapping_sub(layout.size());
-            let aligned_ptr = round_mut_ptr_down_to(ptr, layout.align());
+            let aligned_ptr = if layout.align() <= MIN_ALIGN {
+                // The bump finger is always aligned to `MIN_ALIGN`, so rather
+                // than rounding the pointer down, round the size up to keep
+                // it that way. This cannot overflow because the finger is
+                // aligned and at least `layout.size()`.
+                let size = (layout.size() + MIN_ALIGN - 1) & !(MIN_ALIGN - 1);
+                ptr.wrapping_sub(size)
+            } else {
+                let ptr = ptr.wrapping_sub(layout.size());
+                round_mut_ptr_down_to(ptr, layout.align())
+            };
 
             if aligned_ptr >= start {
                 let aligned_ptr = NonNull::new_unchecked(aligned_ptr);
@@ -1441,6 +3475,40 @@ impl Bump {
         }
     }
 
+    #[inline(always)]
+    unsafe fn try_alloc_layout_fast_upward(
+        &self,
+        footer: &ChunkFooter,
+        layout: Layout,
+    ) -> Option<NonNull<u8>> {
+        let ptr = footer.ptr.get().as_ptr();
+        let end = footer as *const ChunkFooter as usize;
+        let remaining = end - ptr as usize;
+
+        if remaining < layout.size() {
+            return None;
+        }
+
+        // Keep the bump finger aligned to `MIN_ALIGN` by rounding the size up.
+        // This cannot overflow because the size is at most `remaining`.
+        let size = (layout.size() + MIN_ALIGN - 1) & !(MIN_ALIGN - 1);
+        let padding = if layout.align() <= MIN_ALIGN {
+            0
+        } else {
+            (ptr as usize).wrapping_neg() & (layout.align() - 1)
+        };
+
+        if padding <= remaining && size <= remaining - padding {
+            let aligned_ptr = ptr.add(padding);
+            footer
+                .ptr
+                .set(NonNull::new_unchecked(aligned_ptr.add(size)));
+            Some(NonNull::new_unchecked(aligned_ptr))
+        } else {
+            None
+        }
+    }
+
     /// Gets the remaining capacity in the current chunk (in bytes).
     ///
     /// ## Example
@@ -1457,57 +3525,119 @@ impl Bump {
         let current_footer = self.current_chunk_footer.get();
         let current_footer = unsafe { current_footer.as_ref() };
 
-        current_footer.ptr.get().as_ptr() as usize - current_footer.data.as_ptr() as usize
+        if UP {
+            current_footer as *const ChunkFooter as usize
+                - current_footer.ptr.get().as_ptr() as usize
+        } else {
+            current_footer.ptr.get().as_ptr() as usize - current_footer.data.as_ptr() as usize
+        }
+    }
+
+    // Where the bump finger of an unused chunk points to.
+    fn chunk_start(footer: &ChunkFooter) -> NonNull<u8> {
+        if UP {
+            footer.data
+        } else {
+            NonNull::from(footer).cast()
+        }
     }
 
     /// Slow path allocation for when we need to allocate a new chunk from the
     /// parent bump set because there isn't enough room in our current chunk.
+    ///
+    /// If `zeroed` is true, a new chunk is requested zeroed from the backing
+    /// allocator. Also returns whether the allocation is known to be zeroed.
     #[inline(never)]
     #[cold]
-    fn alloc_layout_slow(&self, layout: Layout) -> Option<NonNull<u8>> {
+    fn alloc_layout_slow(
+        &self,
+        layout: Layout,
+        zeroed: bool,
+    ) -> Result<(NonNull<u8>, bool), AllocErrKind> {
+        self.stats.update(|s| s.slow_path_hits += 1);
+
         unsafe {
             let size = layout.size();
             let allocation_limit_remaining = self.allocation_limit_remaining();
 
+            // Refill a chunk that was kept by `reset_with`, if one is big
+            // enough.
+            if let Some(ptr) = self.alloc_layout_in_spare_chunk(layout) {
+                return Ok((ptr, false));
+            }
+
             // Get a new chunk from the global allocator.
             let current_footer = self.current_chunk_footer.get();
             let current_layout = current_footer.as_ref().layout;
 
             // By default, we want our new chunk to be about twice as big
-            // as the previous chunk. If the global allocator refuses it,
-            // we try to divide it by half until it works or the requested
-            // size is smaller than the default footer size.
-            let min_new_chunk_size = layout.size().max(DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER);
-            let mut base_size = (current_layout.size() - FOOTER_SIZE)
-                .checked_mul(2)?
+            // as the previous chunk (or whatever else the chunk policy asks
+            // for). If the global allocator refuses it, we try to divide it by
+            // half until it works or the requested size is smaller than the
+            // minimum chunk size.
+            let chunk_policy = &self.chunk_policy;
+            let min_chunk_size = chunk_policy.min_chunk_size();
+            let min_new_chunk_size = layout.size().max(min_chunk_size);
+            let mut base_size = chunk_policy
+                .next_chunk_size(current_layout.size() - FOOTER_SIZE)
+                .ok_or(AllocErrKind::SizeOverflow)?
                 .max(min_new_chunk_size);
             let chunk_memory_details = iter::from_fn(|| {
                 let bypass_min_chunk_size_for_small_limits = matches!(self.allocation_limit(), Some(limit) if layout.size() < limit
                             && base_size >= layout.size()
-                            && limit < DEFAULT_CHUNK_SIZE_WITHOUT_FOOTER
+                            && limit < min_chunk_size
                             && self.allocated_bytes() == 0);
 
                 if base_size >= min_new_chunk_size || bypass_min_chunk_size_for_small_limits {
                     let size = base_size;
                     base_size /= 2;
-                    Bump::new_chunk_memory_details(Some(size), layout)
+                    Self::new_chunk_memory_details(Some(size), layout, chunk_policy)
                 } else {
                     None
                 }
             });
 
+            // Remember why chunks were not allocated, to report it if none of
+            // them could be. If the backing allocator refused any of them, we
+            // are out of memory, whether or not bigger ones were also over
+            // the allocation limit.
+            let mut over_limit = false;
+            let mut over_budget = false;
+            let mut out_of_memory = false;
             let new_footer = chunk_memory_details
                 .filter_map(|chunk_memory_details| {
-                    if Bump::chunk_fits_under_limit(
+                    if Self::chunk_fits_under_limit(
                         allocation_limit_remaining,
                         chunk_memory_details,
                     ) {
-                        Bump::new_chunk(chunk_memory_details, layout, current_footer)
+                        match self.new_budgeted_chunk(
+                            chunk_memory_details,
+                            layout,
+                            current_footer,
+                            zeroed,
+                        ) {
+                            Ok(footer) => Some(footer),
+                            Err(kind) => {
+                                out_of_memory |= kind == AllocErrKind::Allocator;
+                                over_budget |= kind == AllocErrKind::AllocationBudget;
+                                None
+                            }
+                        }
                     } else {
+                        over_limit = true;
                         None
                     }
                 })
-                .next()?;
+                .next()
+                .ok_or(if out_of_memory {
+                    AllocErrKind::Allocator
+                } else if over_limit {
+                    AllocErrKind::AllocationLimit
+                } else if over_budget {
+                    AllocErrKind::AllocationBudget
+                } else {
+                    AllocErrKind::SizeOverflow
+                })?;
 
             debug_assert_eq!(
                 new_footer.as_ref().data.as_ptr() as usize % layout.align(),
@@ -1515,16 +3645,35 @@ impl Bump {
             );
 
             // Set the new chunk as our new current chunk.
+            self.prev_chunks_used_bytes.set(self.used_bytes());
             self.current_chunk_footer.set(new_footer);
 
             let new_footer = new_footer.as_ref();
 
+            if UP {
+                // The start of the chunk is aligned to at least the requested
+                // alignment, so the allocation goes right there.
+                let ptr = new_footer.ptr.get();
+                let size = round_up_to(size, MIN_ALIGN).ok_or(AllocErrKind::SizeOverflow)?;
+                new_footer
+                    .ptr
+                    .set(NonNull::new_unchecked(ptr.as_ptr().add(size)));
+                debug_assert!(
+                    new_footer.ptr.get().as_ptr() as *const _ <= new_footer,
+                    "{:p} <= {:p}",
+                    new_footer.ptr.get(),
+                    new_footer
+                );
+                return Ok((ptr, zeroed));
+            }
+
             // Move the bump ptr finger down to allocate room for `val`. We know
             // this can't overflow because we successfully allocated a chunk of
             // at least the requested size.
             let mut ptr = new_footer.ptr.get().as_ptr().sub(size);
-            // Round the pointer down to the requested alignment.
-            ptr = round_mut_ptr_down_to(ptr, layout.align());
+            // Round the pointer down to the requested alignment, and keep the
+            // bump finger aligned to `MIN_ALIGN`.
+            ptr = round_mut_ptr_down_to(ptr, layout.align().max(MIN_ALIGN));
             debug_assert!(
                 ptr as *const _ <= new_footer,
                 "{:p} <= {:p}",
@@ -1535,7 +3684,35 @@ impl Bump {
             new_footer.ptr.set(ptr);
 
             // Return a pointer to the freshly allocated region in this chunk.
-            Some(ptr)
+            Ok((ptr, zeroed))
+        }
+    }
+
+    /// Make the first spare chunk that `layout` fits in the current chunk,
+    /// and allocate `layout` in it.
+    unsafe fn alloc_layout_in_spare_chunk(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let size = round_up_to(layout.size(), layout.align().max(MIN_ALIGN))?;
+        let mut link = &self.spare_chunks;
+        loop {
+            let footer = link.get();
+            let f = &*footer.as_ptr();
+            if f.is_empty() {
+                return None;
+            }
+            if f.capacity() >= size && is_pointer_aligned_to(f.data.as_ptr(), layout.align()) {
+                let current = self.current_chunk_footer.get();
+                link.set(f.prev.replace(current));
+                self.spare_bytes.set(self.spare_bytes.get() - f.capacity());
+                self.prev_chunks_used_bytes.set(self.used_bytes());
+                (*footer.as_ptr()).allocated_bytes =
+                    current.as_ref().allocated_bytes + f.capacity();
+                self.current_chunk_footer.set(footer);
+
+                let ptr = self.try_alloc_layout_fast(layout);
+                debug_assert!(ptr.is_some());
+                return ptr;
+            }
+            link = &f.prev;
         }
     }
 
@@ -1548,7 +3725,9 @@ impl Bump {
     ///
     /// The values inside each chunk are also ordered by allocation time, with
     /// the most recent allocation being earlier in the slice, and the least
-    /// recent allocation being towards the end of the slice.
+    /// recent allocation being towards the end of the slice. In an
+    /// [upward bumping](#upward-bumping) arena, this order is the other way
+    /// around: the least recent allocation comes first.
     ///
     /// ## Safety
     ///
@@ -1612,6 +3791,8 @@ impl Bump {
     ///
     /// assert_eq!(bump.iter_allocated_chunks().count(), 1);
     /// let chunk = bump.iter_allocated_chunks().nth(0).unwrap();
+    /// # // The red zones of `debug-checks` sit between the allocations.
+    /// # #[cfg(not(feature = "debug-checks"))] {
     /// assert_eq!(chunk.len(), 3);
     ///
     /// // Safe because we've only allocated `u8`s in this arena, which
@@ -1621,6 +3802,7 @@ impl Bump {
     ///     assert_eq!(chunk[1].assume_init(), b'b');
     ///     assert_eq!(chunk[2].assume_init(), b'a');
     /// }
+    /// # }
     /// ```
     pub fn iter_allocated_chunks(&mut self) -> ChunkIter<'_> {
         // SAFE: Ensured by mutable borrow of `self`.
@@ -1651,6 +3833,7 @@ impl Bump {
     pub unsafe fn iter_allocated_chunks_raw(&self) -> ChunkRawIter<'_> {
         ChunkRawIter {
             footer: self.current_chunk_footer.get(),
+            upward: UP,
             bump: PhantomData,
         }
     }
@@ -1679,8 +3862,7 @@ impl Bump {
     /// ```
     pub fn allocated_bytes(&self) -> usize {
         let footer = self.current_chunk_footer.get();
-
-        unsafe { footer.as_ref().allocated_bytes }
+        unsafe { footer.as_ref().allocated_bytes + self.spare_bytes.get() }
     }
 
     /// Calculates the number of bytes requested from the Rust allocator for this `Bump`.
@@ -1688,26 +3870,333 @@ impl Bump {
     /// This number is equal to the [`allocated_bytes()`](Self::allocated_bytes) plus
     /// the size of the bump metadata.
     pub fn allocated_bytes_including_metadata(&self) -> usize {
+        let chunks = unsafe { self.iter_allocated_chunks_raw().count() };
         let metadata_size =
-            unsafe { self.iter_allocated_chunks_raw().count() * mem::size_of::<ChunkFooter>() };
+            (chunks + self.iter_spare_chunks().count()) * mem::size_of::<ChunkFooter>();
         self.allocated_bytes() + metadata_size
     }
 
+    /// Get statistics about the allocations that were made in this arena
+    /// since it was created.
+    ///
+    /// This method is only available with the `stats` cargo feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut bump = bumpalo::Bump::new();
+    /// bump.alloc(1_u8);
+    /// bump.alloc(2_u32);
+    /// bump.reset();
+    /// bump.alloc(3_u64);
+    ///
+    /// let stats = bump.stats();
+    /// assert_eq!(stats.allocations, 3);
+    /// assert_eq!(stats.bytes_requested, 13);
+    /// assert!(stats.bytes_consumed >= stats.bytes_requested);
+    /// assert!(stats.high_water_mark >= 5);
+    /// ```
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.record_high_water_mark();
+        self.stats.get()
+    }
+
+    /// Iterate over the footers of this arena's chunks, from the current one
+    /// to the oldest one, ending with the canonical empty chunk.
+    fn iter_footers(&self) -> impl Iterator<Item = NonNull<ChunkFooter>> {
+        let mut next = Some(self.current_chunk_footer.get());
+        iter::from_fn(move || {
+            let footer = next?;
+            let f = unsafe { footer.as_ref() };
+            next = if f.is_empty() {
+                None
+            } else {
+                Some(f.prev.get())
+            };
+            Some(footer)
+        })
+    }
+
+    /// Iterate over the chunks that were kept by `reset_with` and have not
+    /// been bump allocated into again yet.
+    fn iter_spare_chunks(&self) -> impl Iterator<Item = &ChunkFooter> {
+        let mut next = self.spare_chunks.get();
+        iter::from_fn(move || {
+            let f = unsafe { &*next.as_ptr() };
+            if f.is_empty() {
+                None
+            } else {
+                next = f.prev.get();
+                Some(f)
+            }
+        })
+    }
+
+    // The number of bytes that are in use in this arena's chunks.
+    fn used_bytes(&self) -> usize {
+        let footer = self.current_chunk_footer.get();
+        self.prev_chunks_used_bytes.get() + unsafe { footer.as_ref().as_raw_parts(UP).1 }
+    }
+
+    // Count an allocation, or an allocation growing in place, that moved the
+    // bump finger from where it was at `before`.
+    #[cfg(feature = "stats")]
+    fn record_allocation(&self, new_allocation: bool, requested: usize, before: Checkpoint) {
+        let footer = self.current_chunk_footer.get();
+        let consumed = unsafe {
+            if footer == before.footer {
+                let ptr = footer.as_ref().ptr.get().as_ptr() as usize;
+                ptr.abs_diff(before.ptr.as_ptr() as usize)
+            } else {
+                // The allocation moved on to an empty chunk, so it is the
+                // only thing in there.
+                footer.as_ref().as_raw_parts(UP).1
+            }
+        };
+        self.stats.update(|s| {
+            s.allocations += usize::from(new_allocation);
+            s.bytes_requested += requested;
+            s.bytes_consumed += consumed;
+        });
+    }
+
+    // Count the bytes that are in use right now towards the high-water mark
+    // in this arena's statistics. This needs to happen before anything lowers
+    // the number of bytes in use: deallocating, shrinking, resetting or
+    // rolling back.
+    #[inline]
+    fn record_high_water_mark(&self) {
+        self.stats
+            .update(|s| s.high_water_mark = s.high_water_mark.max(self.used_bytes()));
+    }
+
+    /// Check that no allocation in this arena was written out of bounds.
+    ///
+    /// With the `debug-checks` cargo feature, every allocation in an arena
+    /// built with [`BumpBuilder::red_zones(true)`][BumpBuilder::red_zones]
+    /// comes with a red zone of canary bytes right where writing past its end
+    /// would land. This method checks that the red zones of all live allocations are
+    /// still intact, and reports the allocation next to the first one that
+    /// isn't. The red zones are also checked when the arena is reset or
+    /// dropped, which panics if one was overwritten.
+    ///
+    /// The feature also fills fresh allocations in every arena with `0xCD`
+    /// bytes, and fills memory with `0xDD` bytes when the arena is reset or
+    /// its last allocation is deallocated, so that reading uninitialized or
+    /// freed memory stands out in a debugger.
+    ///
+    /// This method is only available with the `debug-checks` cargo feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use std::alloc::Layout;
+    ///
+    /// let bump = bumpalo::Bump::builder().red_zones(true).build();
+    /// let p = bump.alloc_layout(Layout::new::<[u8; 4]>()).as_ptr();
+    /// bump.alloc(1_u32);
+    /// assert!(bump.check_integrity().is_ok());
+    ///
+    /// // Write one byte past the end of the first allocation.
+    /// unsafe { p.add(4).write(42) };
+    ///
+    /// let err = bump.check_integrity().unwrap_err();
+    /// assert_eq!(err.allocation(), p.cast_const());
+    /// assert_eq!(err.corrupted(), p.wrapping_add(4).cast_const());
+    /// # // Don't panic when the arena is dropped.
+    /// # unsafe { p.add(4).write(0xFD) };
+    /// ```
+    #[cfg(feature = "debug-checks")]
+    pub fn check_integrity(&self) -> Result<(), IntegrityError> {
+        unsafe { self.red_zones.check(UP) }
+    }
+
+    #[cfg(feature = "debug-checks")]
+    fn assert_integrity(&self) {
+        if let Err(e) = self.check_integrity() {
+            // Only report the overrun once, rather than again when the arena
+            // is dropped while unwinding.
+            self.red_zones.clear();
+            panic!("{}", e);
+        }
+    }
+
+    // The layout to bump allocate for `layout` and its red zone, and the
+    // offset of the allocation within it.
+    #[cfg(feature = "debug-checks")]
+    #[inline]
+    fn guarded_layout(&self, layout: Layout) -> Result<(Layout, usize), AllocErr> {
+        if !self.red_zones.is_enabled() {
+            return Ok((layout, 0));
+        }
+        debug_checks::guarded_layout(layout, MIN_ALIGN, UP)
+            .ok_or_else(|| AllocErr::new(AllocErrKind::SizeOverflow, Some(layout), None))
+    }
+
+    // Fill in a fresh allocation of `layout` and its red zone, which were
+    // bump allocated at `block` when the bump finger was at `finger`, and
+    // return the allocation.
+    #[cfg(feature = "debug-checks")]
+    unsafe fn guard_allocation(
+        &self,
+        block: NonNull<u8>,
+        offset: usize,
+        layout: Layout,
+        finger: Checkpoint,
+    ) -> NonNull<u8> {
+        let allocation = block.as_ptr().add(offset);
+        ptr::write_bytes(allocation, debug_checks::FRESH_BYTE, layout.size());
+        if !self.red_zones.is_enabled() {
+            return block;
+        }
+
+        // Padding for alignment between the allocation and the previous one
+        // in the same chunk is part of the red zone too.
+        let footer = self.current_chunk_footer.get();
+        let (start, end) = if UP {
+            let start = if footer == finger.footer {
+                finger.ptr.as_ptr()
+            } else {
+                block.as_ptr()
+            };
+            (start, allocation)
+        } else {
+            let size =
+                round_up_to(layout.size(), MIN_ALIGN).unwrap_or_else(allocation_size_overflow);
+            let start = allocation.add(size);
+            let end = if footer == finger.footer {
+                finger.ptr.as_ptr()
+            } else {
+                start.add(debug_checks::RED_ZONE_SIZE)
+            };
+            (start, end)
+        };
+        let len = end as usize - start as usize;
+        self.poisoning.unpoison(start, len);
+        ptr::write_bytes(start, debug_checks::CANARY_BYTE, len);
+        self.red_zones.push(debug_checks::RedZone {
+            chunk: footer.as_ptr() as usize,
+            start: start as usize,
+            len,
+            allocation: allocation as usize,
+            layout,
+        });
+        NonNull::new_unchecked(allocation)
+    }
+
+    // Fill in the new bytes of the allocation at `old_ptr` after it grew in
+    // place to `new_layout` at `new_ptr`.
+    #[cfg(feature = "debug-checks")]
+    unsafe fn guard_growth(
+        &self,
+        old_ptr: NonNull<u8>,
+        new_ptr: NonNull<u8>,
+        old_size: usize,
+        new_layout: Layout,
+    ) {
+        ptr::write_bytes(
+            new_ptr.as_ptr().add(old_size),
+            debug_checks::FRESH_BYTE,
+            new_layout.size() - old_size,
+        );
+        self.red_zones.update_newest(
+            old_ptr.as_ptr() as usize,
+            new_ptr.as_ptr() as usize,
+            new_layout,
+        );
+    }
+
+    // Forget the red zones of allocations whose memory was rolled back.
+    #[cfg(feature = "debug-checks")]
+    fn forget_dead_red_zones(&self) {
+        self.red_zones.forget_dead(|zone| {
+            self.iter_footers().any(|footer| {
+                let footer = unsafe { footer.as_ref() };
+                let (start, len) = footer.as_raw_parts(UP);
+                let start = start as usize;
+                footer as *const ChunkFooter as usize == zone.chunk
+                    && start <= zone.start
+                    && zone.start + zone.len <= start + len
+            })
+        });
+    }
+
+    // Fill the bytes that are in use in the chunk with `footer` with the
+    // pattern for freed memory, before the chunk is reset.
+    #[cfg(feature = "debug-checks")]
+    unsafe fn fill_freed_chunk(&self, footer: &ChunkFooter) {
+        let (start, len) = footer.as_raw_parts(UP);
+        // Padding between allocations is still poisoned.
+        self.poisoning.unpoison(start, len);
+        ptr::write_bytes(start as *mut u8, debug_checks::FREED_BYTE, len);
+    }
+
+    // Poison the part of the chunk with `footer` that hasn't been allocated.
+    unsafe fn poison_free_space(&self, footer: &ChunkFooter) {
+        let finger = footer.ptr.get().as_ptr();
+        let (start, end) = if UP {
+            (
+                finger as *const u8,
+                footer as *const ChunkFooter as *const u8,
+            )
+        } else {
+            (footer.data.as_ptr() as *const u8, finger as *const u8)
+        };
+        self.poisoning.poison(start, end as usize - start as usize);
+    }
+
     #[inline]
-    unsafe fn is_last_allocation(&self, ptr: NonNull<u8>) -> bool {
+    unsafe fn is_last_allocation(&self, ptr: NonNull<u8>, size: usize) -> bool {
         let footer = self.current_chunk_footer.get();
         let footer = footer.as_ref();
-        footer.ptr.get() == ptr
+        if UP {
+            // The last allocation ends where the bump finger is, taking the
+            // rounding to `MIN_ALIGN` into account.
+            let size = round_down_to(size.wrapping_add(MIN_ALIGN - 1), MIN_ALIGN);
+            footer.ptr.get().as_ptr() as usize == (ptr.as_ptr() as usize).wrapping_add(size)
+        } else {
+            footer.ptr.get() == ptr
+        }
     }
 
     #[inline]
     unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
         // If the pointer is the last allocation we made, we can reuse the bytes,
         // otherwise they are simply leaked -- at least until somebody calls reset().
-        if self.is_last_allocation(ptr) {
-            let ptr = self.current_chunk_footer.get().as_ref().ptr.get();
-            let ptr = NonNull::new_unchecked(ptr.as_ptr().add(layout.size()));
-            self.current_chunk_footer.get().as_ref().ptr.set(ptr);
+        if self.is_last_allocation(ptr, layout.size()) {
+            let footer = self.current_chunk_footer.get();
+            let old_ptr = footer.as_ref().ptr.get();
+            let new_ptr = if UP {
+                ptr
+            } else {
+                // Round up to keep the bump finger aligned to `MIN_ALIGN`.
+                // This stays within the allocation's original footprint
+                // because the allocation itself is aligned to at least
+                // `MIN_ALIGN`.
+                let size =
+                    round_up_to(layout.size(), MIN_ALIGN).unwrap_or_else(allocation_size_overflow);
+                NonNull::new_unchecked(old_ptr.as_ptr().add(size))
+            };
+            self.record_high_water_mark();
+            footer.as_ref().ptr.set(new_ptr);
+            self.stats.update(|s| {
+                s.bytes_reclaimed += (new_ptr.as_ptr() as usize).abs_diff(old_ptr.as_ptr() as usize)
+            });
+            #[cfg(feature = "debug-checks")]
+            {
+                ptr::write_bytes(ptr.as_ptr(), debug_checks::FREED_BYTE, layout.size());
+                self.red_zones.forget_newest(ptr.as_ptr() as usize);
+            }
+            let (start, end) = if UP {
+                (new_ptr, old_ptr)
+            } else {
+                (old_ptr, new_ptr)
+            };
+            self.poisoning.poison(
+                start.as_ptr(),
+                end.as_ptr() as usize - start.as_ptr() as usize,
+            );
         }
     }
 
@@ -1735,7 +4224,7 @@ impl Bump {
             if is_pointer_aligned_to(ptr.as_ptr(), new_layout.align()) {
                 return Ok(ptr);
             } else {
-                return Err(AllocErr);
+                return Err(AllocErr::unsupported(new_layout));
             }
         }
 
@@ -1744,11 +4233,39 @@ impl Bump {
         let old_size = old_layout.size();
         let new_size = new_layout.size();
 
+        if UP {
+            // The allocation ends at the bump finger, so shrinking it only
+            // moves the finger back and never needs a copy.
+            if self.is_last_allocation(ptr, old_size) {
+                let size =
+                    round_up_to(new_size, MIN_ALIGN).unwrap_or_else(allocation_size_overflow);
+                let new_end = NonNull::new_unchecked(ptr.as_ptr().add(size));
+                let footer = self.current_chunk_footer.get();
+                self.record_high_water_mark();
+                let old_end = footer.as_ref().ptr.replace(new_end);
+                self.stats.update(|s| {
+                    s.bytes_reclaimed += old_end.as_ptr() as usize - new_end.as_ptr() as usize
+                });
+                self.poisoning.poison(
+                    new_end.as_ptr(),
+                    old_end.as_ptr() as usize - new_end.as_ptr() as usize,
+                );
+                #[cfg(feature = "debug-checks")]
+                self.red_zones.update_newest(
+                    ptr.as_ptr() as usize,
+                    ptr.as_ptr() as usize,
+                    new_layout,
+                );
+            }
+            return Ok(ptr);
+        }
+
         // This is how much space we would *actually* reclaim while satisfying
-        // the requested alignment.
-        let delta = round_down_to(old_size - new_size, new_layout.align());
+        // the requested alignment and keeping the bump finger aligned to
+        // `MIN_ALIGN`.
+        let delta = round_down_to(old_size - new_size, new_layout.align().max(MIN_ALIGN));
 
-        if self.is_last_allocation(ptr)
+        if self.is_last_allocation(ptr, old_size)
                 // Only reclaim the excess space (which requires a copy) if it
                 // is worth it: we are actually going to recover "enough" space
                 // and we can do a non-overlapping copy.
@@ -1785,11 +4302,21 @@ impl Bump {
             // NB: new_ptr is aligned, because ptr *has to* be aligned, and we
             // made sure delta is aligned.
             let new_ptr = NonNull::new_unchecked(footer.ptr.get().as_ptr().add(delta));
+            self.record_high_water_mark();
             footer.ptr.set(new_ptr);
+            self.stats.update(|s| s.bytes_reclaimed += delta);
 
             // NB: we know it is non-overlapping because of the size check
             // in the `if` condition.
             ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), new_size);
+            self.poisoning.poison(ptr.as_ptr(), delta);
+
+            #[cfg(feature = "debug-checks")]
+            self.red_zones.update_newest(
+                ptr.as_ptr() as usize,
+                new_ptr.as_ptr() as usize,
+                new_layout,
+            );
 
             return Ok(new_ptr);
         }
@@ -1810,14 +4337,46 @@ impl Bump {
         let new_size = new_layout.size();
         let align_is_compatible = old_layout.align() >= new_layout.align();
 
-        if align_is_compatible && self.is_last_allocation(ptr) {
+        if UP && align_is_compatible && self.is_last_allocation(ptr, old_size) {
+            // The allocation ends at the bump finger, so if there is enough
+            // room left in the chunk, growing it only moves the finger.
+            let footer = self.current_chunk_footer.get();
+            let footer = footer.as_ref();
+            let available = footer as *const ChunkFooter as usize - ptr.as_ptr() as usize;
+            if new_size <= available {
+                // Cannot overflow because `available` is a multiple of
+                // `MIN_ALIGN`.
+                let size =
+                    round_up_to(new_size, MIN_ALIGN).unwrap_or_else(allocation_size_overflow);
+                #[cfg(feature = "stats")]
+                let before = self.checkpoint();
+                footer
+                    .ptr
+                    .set(NonNull::new_unchecked(ptr.as_ptr().add(size)));
+                self.poisoning
+                    .unpoison(ptr.as_ptr().add(old_size), new_size - old_size);
+                #[cfg(feature = "stats")]
+                self.record_allocation(false, new_size - old_size, before);
+                #[cfg(feature = "debug-checks")]
+                self.guard_growth(ptr, ptr, old_size, new_layout);
+                return Ok(ptr);
+            }
+        } else if !UP && align_is_compatible && self.is_last_allocation(ptr, old_size) {
             // Try to allocate the delta size within this same block so we can
             // reuse the currently allocated space.
             let delta = new_size - old_size;
+            #[cfg(feature = "stats")]
+            let before = self.checkpoint();
             if let Some(p) =
                 self.try_alloc_layout_fast(layout_from_size_align(delta, old_layout.align())?)
             {
+                #[cfg(feature = "stats")]
+                self.record_allocation(false, delta, before);
+                self.poisoning
+                    .unpoison(p.as_ptr(), ptr.as_ptr() as usize - p.as_ptr() as usize);
                 ptr::copy(ptr.as_ptr(), p.as_ptr(), old_size);
+                #[cfg(feature = "debug-checks")]
+                self.guard_growth(ptr, p, old_size, new_layout);
                 return Ok(p);
             }
         }
@@ -1829,6 +4388,175 @@ impl Bump {
     }
 }
 
+// A string at the end of a `Bump` that is formatted into, growing through
+// `Bump::grow`, which only reuses its space while it is the last allocation.
+struct StrWriter<'a, A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> {
+    bump: &'a Bump<A, MIN_ALIGN, UP>,
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    // Whether running out of memory is handled like in `Bump::alloc_layout`.
+    infallible: bool,
+    // Why writing failed, if it was because memory ran out.
+    error: Option<AllocErr>,
+}
+
+impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> StrWriter<'_, A, MIN_ALIGN, UP> {
+    // Doubling the capacity keeps the total work of moving the string down, or
+    // copying it to a new chunk, linear in its final length.
+    fn grow(&mut self, additional: usize) -> Result<(), AllocErr> {
+        let cap = self
+            .len
+            .checked_add(additional)
+            .ok_or_else(AllocErr::size_overflow)?
+            .max(self.cap.saturating_mul(2))
+            .max(16);
+        let old_layout = layout_from_size_align(self.cap, 1)?;
+        let new_layout = layout_from_size_align(cap, 1)?;
+        self.ptr = match unsafe { self.bump.grow(self.ptr, old_layout, new_layout) } {
+            Ok(p) => p,
+            Err(_) if self.infallible => unsafe {
+                let p = self.bump.handle_oom(new_layout);
+                ptr::copy_nonoverlapping(self.ptr.as_ptr(), p.as_ptr(), self.len);
+                p
+            },
+            Err(e) => return Err(e),
+        };
+        self.cap = cap;
+        Ok(())
+    }
+}
+
+impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> fmt::Write
+    for StrWriter<'_, A, MIN_ALIGN, UP>
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() > self.cap - self.len {
+            if let Err(e) = self.grow(s.len()) {
+                self.error = Some(e);
+                return Err(fmt::Error);
+            }
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(s.as_ptr(), self.ptr.as_ptr().add(self.len), s.len());
+        }
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+// The elements of a slice that were initialized so far, which are dropped
+// along with it unless it is forgotten.
+struct InitializedPrefix<T> {
+    dst: *mut T,
+    len: usize,
+}
+
+impl<T> Drop for InitializedPrefix<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.dst, self.len));
+        }
+    }
+}
+
+/// What an allocation that can't fail should do when it runs out of memory,
+/// as decided by the handler set with [`Bump::set_oom_handler`].
+///
+/// [`Bump::set_oom_handler`]: struct.Bump.html#method.set_oom_handler
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OomAction {
+    /// Try to allocate again, for example after raising the arena's
+    /// allocation limit or freeing memory elsewhere. If that fails too, the
+    /// handler is called again.
+    Retry,
+
+    /// Panic with an "out of memory" message. This is what happens when no
+    /// handler was set.
+    Panic,
+
+    /// Abort the process without unwinding.
+    Abort,
+}
+
+/// The view of an arena that its out-of-memory handler gets, as set with
+/// [`Bump::set_oom_handler`].
+///
+/// [`Bump::set_oom_handler`]: struct.Bump.html#method.set_oom_handler
+pub trait OomContext {
+    /// The number of bytes currently allocated across all of the arena's
+    /// chunks. See [`Bump::allocated_bytes`].
+    ///
+    /// [`Bump::allocated_bytes`]: struct.Bump.html#method.allocated_bytes
+    fn allocated_bytes(&self) -> usize;
+
+    /// The arena's allocation limit, if it has one. See
+    /// [`Bump::allocation_limit`].
+    ///
+    /// [`Bump::allocation_limit`]: struct.Bump.html#method.allocation_limit
+    fn allocation_limit(&self) -> Option<usize>;
+
+    /// Set or lift the arena's allocation limit. See
+    /// [`Bump::set_allocation_limit`].
+    ///
+    /// [`Bump::set_allocation_limit`]: struct.Bump.html#method.set_allocation_limit
+    fn set_allocation_limit(&self, limit: Option<usize>);
+}
+
+impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> OomContext
+    for Bump<A, MIN_ALIGN, UP>
+{
+    fn allocated_bytes(&self) -> usize {
+        Bump::allocated_bytes(self)
+    }
+
+    fn allocation_limit(&self) -> Option<usize> {
+        Bump::allocation_limit(self)
+    }
+
+    fn set_allocation_limit(&self, limit: Option<usize>) {
+        Bump::set_allocation_limit(self, limit)
+    }
+}
+
+/// Which chunks [`Bump::reset_with`] keeps for future allocations.
+///
+/// [`Bump::reset_with`]: struct.Bump.html#method.reset_with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetainPolicy {
+    /// Keep every chunk.
+    All,
+
+    /// Keep the oldest chunks whose sizes add up to at most the given number
+    /// of bytes, not counting `bumpalo`'s metadata.
+    UpTo(usize),
+
+    /// Keep a single chunk that is big enough for the most bytes that were in
+    /// use at once since the arena was last reset, including in
+    /// [`scope`][crate::Bump::scope]s and before calls to
+    /// [`reset_to`][crate::Bump::reset_to].
+    ///
+    /// The smallest existing chunk that is big enough is kept if there is
+    /// one, and otherwise a new chunk is allocated.
+    Merge,
+}
+
+/// A record of a [`Bump`]'s allocation state at some point in time.
+///
+/// This struct is created by the [`checkpoint`] method on [`Bump`] and can be
+/// passed to [`reset_to`] to deallocate everything that was allocated since.
+///
+/// [`Bump`]: struct.Bump.html
+/// [`checkpoint`]: struct.Bump.html#method.checkpoint
+/// [`reset_to`]: struct.Bump.html#method.reset_to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    // The chunk that was current when the checkpoint was recorded.
+    footer: NonNull<ChunkFooter>,
+    // That chunk's bump finger when the checkpoint was recorded.
+    ptr: NonNull<u8>,
+}
+
 /// An iterator over each chunk of allocated memory that
 /// an arena has bump allocated into.
 ///
@@ -1836,7 +4564,7 @@ impl Bump {
 /// allocated chunk being returned first.
 ///
 /// The values inside each chunk are also ordered by allocation time, with the most
-/// recent allocation being earlier in the slice.
+/// recent allocation being earlier in the slice, unless the arena bumps upwards.
 ///
 /// This struct is created by the [`iter_allocated_chunks`] method on
 /// [`Bump`]. See that function for a safety description regarding reading from the returned items.
@@ -1876,6 +4604,7 @@ impl<'a> iter::FusedIterator for ChunkIter<'a> {}
 #[derive(Debug)]
 pub struct ChunkRawIter<'a> {
     footer: NonNull<ChunkFooter>,
+    upward: bool,
     bump: PhantomData<&'a Bump>,
 }
 
@@ -1887,7 +4616,7 @@ impl Iterator for ChunkRawIter<'_> {
             if foot.is_empty() {
                 return None;
             }
-            let (ptr, len) = foot.as_raw_parts();
+            let (ptr, len) = foot.as_raw_parts(self.upward);
             self.footer = foot.prev.get();
             Some((ptr as *mut u8, len))
         }
@@ -1896,13 +4625,44 @@ impl Iterator for ChunkRawIter<'_> {
 
 impl iter::FusedIterator for ChunkRawIter<'_> {}
 
+// Whether the current thread is panicking, as far as we can tell.
+#[cfg(feature = "debug-checks")]
+fn thread_is_panicking() -> bool {
+    #[cfg(feature = "std")]
+    return std::thread::panicking();
+    #[cfg(not(feature = "std"))]
+    false
+}
+
 #[inline(never)]
 #[cold]
 fn oom() -> ! {
     panic!("out of memory")
 }
 
-unsafe impl<'a> alloc::Alloc for &'a Bump {
+#[inline(never)]
+#[cold]
+fn abort() -> ! {
+    #[cfg(feature = "std")]
+    std::process::abort();
+
+    #[cfg(not(feature = "std"))]
+    {
+        // Panicking while already panicking aborts.
+        struct PanicOnDrop;
+        impl Drop for PanicOnDrop {
+            fn drop(&mut self) {
+                panic!("out of memory")
+            }
+        }
+        let _guard = PanicOnDrop;
+        panic!("out of memory")
+    }
+}
+
+unsafe impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> alloc::Alloc
+    for &Bump<A, MIN_ALIGN, UP>
+{
     #[inline(always)]
     unsafe fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
         self.try_alloc_layout(layout)
@@ -1935,8 +4695,9 @@ unsafe impl<'a> alloc::Alloc for &'a Bump {
     }
 }
 
-#[cfg(any(feature = "allocator_api", feature = "allocator-api2"))]
-unsafe impl<'a> Allocator for &'a Bump {
+unsafe impl<A: BackingAllocator, const MIN_ALIGN: usize, const UP: bool> Allocator
+    for &Bump<A, MIN_ALIGN, UP>
+{
     #[inline]
     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
         self.try_alloc_layout(layout)
@@ -1946,6 +4707,15 @@ unsafe impl<'a> Allocator for &'a Bump {
             .map_err(|_| AllocError)
     }
 
+    #[inline]
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.try_alloc_layout_zeroed(layout)
+            .map(|p| unsafe {
+                NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(p.as_ptr(), layout.size()))
+            })
+            .map_err(|_| AllocError)
+    }
+
     #[inline]
     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
         Bump::dealloc(self, ptr, layout)
@@ -2037,7 +4807,7 @@ mod tests {
 
             // `realloc` will allocate a new chunk when growing the last
             // allocation, if need be.
-            let layout = Layout::from_size_align(1, 1).unwrap();
+            let layout = Layout::new::<u8>();
             let p = b.alloc_layout(layout);
             let q = (&b).realloc(p, layout, CAPACITY + 1).unwrap();
             assert!(q.as_ptr() as usize != p.as_ptr() as usize - CAPACITY);
@@ -2045,7 +4815,7 @@ mod tests {
 
             // `realloc` will allocate and copy when reallocating anything that
             // wasn't the last allocation.
-            let layout = Layout::from_size_align(1, 1).unwrap();
+            let layout = Layout::new::<u8>();
             let p = b.alloc_layout(layout);
             let _ = b.alloc_layout(layout);
             let q = (&b).realloc(p, layout, 2).unwrap();
@@ -2073,4 +4843,32 @@ mod tests {
             b.realloc(p1, l3, 48000).unwrap();
         }
     }
+
+    // Uses our private `alloc` module and `Bump::shrink`.
+    #[test]
+    fn unsupported_requests_are_reported() {
+        use alloc::Alloc;
+
+        let mut b = &Bump::new();
+        let err = b.alloc_one::<()>().unwrap_err();
+        assert_eq!(err.kind(), AllocErrKind::Unsupported);
+        let err = b.alloc_array::<u64>(0).unwrap_err();
+        assert_eq!(err.kind(), AllocErrKind::Unsupported);
+        let err = b.alloc_array::<u64>(usize::MAX).unwrap_err();
+        assert_eq!(err.kind(), AllocErrKind::SizeOverflow);
+
+        unsafe {
+            // Shrinking to a stricter alignment that the allocation happens
+            // not to have would need a new allocation.
+            let old = Layout::from_size_align(15, 1).unwrap();
+            let new = Layout::from_size_align(8, 8).unwrap();
+            let mut p = b.alloc_layout(old);
+            while is_pointer_aligned_to(p.as_ptr(), 8) {
+                p = b.alloc_layout(old);
+            }
+            let err = b.shrink(p, old, new).unwrap_err();
+            assert_eq!(err.kind(), AllocErrKind::Unsupported);
+            assert_eq!(err.layout(), Some(new));
+        }
+    }
 }
@@ -0,0 +1,103 @@
+//! Telling AddressSanitizer and Valgrind which bytes of a `Bump`'s chunks are
+//! allocated, used only when the `sanitize` cargo feature is enabled.
+//!
+//! The unallocated part of every chunk is poisoned, so that reading or writing
+//! it is reported as an error. AddressSanitizer is told through its manual
+//! poisoning interface, when the crate is built with `-Zsanitizer=address`
+//! (see `build.rs`). Valgrind is told through client requests, which do
+//! nothing when the program isn't running under Valgrind.
+
+// The memory of a `Bump` that is poisoned. Without the `sanitize` feature,
+// nothing is.
+#[derive(Debug, Default)]
+pub(crate) struct Poisoning {}
+
+impl Poisoning {
+    /// Mark the `len` bytes at `ptr` as off limits.
+    #[inline]
+    pub(crate) fn poison(&self, ptr: *const u8, len: usize) {
+        #[cfg(feature = "sanitize")]
+        if len > 0 {
+            unsafe {
+                #[cfg(bumpalo_asan)]
+                __asan_poison_memory_region(ptr, len);
+                valgrind::make_mem_noaccess(ptr, len);
+            }
+        }
+        let _ = (ptr, len);
+    }
+
+    /// Mark the `len` bytes at `ptr` as allocated, but not initialized.
+    #[inline]
+    pub(crate) fn unpoison(&self, ptr: *const u8, len: usize) {
+        #[cfg(feature = "sanitize")]
+        if len > 0 {
+            unsafe {
+                #[cfg(bumpalo_asan)]
+                __asan_unpoison_memory_region(ptr, len);
+                valgrind::make_mem_undefined(ptr, len);
+            }
+        }
+        let _ = (ptr, len);
+    }
+}
+
+#[cfg(bumpalo_asan)]
+extern "C" {
+    fn __asan_poison_memory_region(addr: *const u8, size: usize);
+    fn __asan_unpoison_memory_region(addr: *const u8, size: usize);
+}
+
+/// Valgrind's client requests, as defined by `valgrind.h` and `memcheck.h`.
+#[cfg(feature = "sanitize")]
+mod valgrind {
+    const MAKE_MEM_NOACCESS: usize = 0x4d43_0000;
+    const MAKE_MEM_UNDEFINED: usize = 0x4d43_0001;
+
+    pub(super) unsafe fn make_mem_noaccess(ptr: *const u8, len: usize) {
+        client_request(MAKE_MEM_NOACCESS, ptr as usize, len);
+    }
+
+    pub(super) unsafe fn make_mem_undefined(ptr: *const u8, len: usize) {
+        client_request(MAKE_MEM_UNDEFINED, ptr as usize, len);
+    }
+
+    // Valgrind recognizes a request by the sequence of instructions that
+    // rotate a register by a total of 128 bits before a no-op instruction.
+    // Natively, the sequence doesn't do anything.
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    #[inline(always)]
+    unsafe fn client_request(request: usize, arg1: usize, arg2: usize) {
+        let args: [usize; 6] = [request, arg1, arg2, 0, 0, 0];
+        core::arch::asm!(
+            "rol rdi, 3",
+            "rol rdi, 13",
+            "rol rdi, 61",
+            "rol rdi, 51",
+            "xchg rbx, rbx",
+            inout("rdx") 0_usize => _,
+            in("rax") args.as_ptr(),
+            options(nostack),
+        );
+    }
+
+    #[cfg(all(target_arch = "aarch64", not(miri)))]
+    #[inline(always)]
+    unsafe fn client_request(request: usize, arg1: usize, arg2: usize) {
+        let args: [usize; 6] = [request, arg1, arg2, 0, 0, 0];
+        core::arch::asm!(
+            "ror x12, x12, #3",
+            "ror x12, x12, #13",
+            "ror x12, x12, #51",
+            "ror x12, x12, #61",
+            "orr x10, x10, x10",
+            inout("x3") 0_usize => _,
+            in("x4") args.as_ptr(),
+            options(nostack),
+        );
+    }
+
+    #[cfg(not(all(any(target_arch = "x86_64", target_arch = "aarch64"), not(miri))))]
+    #[inline(always)]
+    unsafe fn client_request(_request: usize, _arg1: usize, _arg2: usize) {}
+}
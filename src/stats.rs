@@ -0,0 +1,86 @@
+//! Allocation statistics for a `Bump`, kept only when the `stats` cargo
+//! feature is enabled.
+//!
+//! See [`Stats`] for details.
+//!
+//! [`Stats`]: ../struct.Stats.html
+
+#[cfg(feature = "stats")]
+use core::cell::Cell;
+
+/// Statistics about the allocations made in a [`Bump`] over its whole
+/// lifetime, as returned by [`Bump::stats`].
+///
+/// Resetting an arena doesn't reset its statistics.
+///
+/// This struct is only available with the `stats` cargo feature. Keeping
+/// track of the statistics has a small cost on every allocation, which isn't
+/// paid when the feature is disabled.
+///
+/// [`Bump`]: struct.Bump.html
+/// [`Bump::stats`]: struct.Bump.html#method.stats
+#[cfg_attr(not(feature = "stats"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// The number of successful allocations.
+    ///
+    /// Growing the most recent allocation in place doesn't count as another
+    /// allocation, but growing it into a new allocation does.
+    pub allocations: usize,
+
+    /// The number of bytes that were asked for by the allocations, and by
+    /// growing allocations in place.
+    pub bytes_requested: usize,
+
+    /// The number of bytes that the allocations actually used up in the
+    /// arena's chunks, including padding for alignment, but not the space
+    /// left unused at the end of a chunk when a new one was needed.
+    pub bytes_consumed: usize,
+
+    /// The number of chunks that were allocated from the backing allocator.
+    pub chunks_allocated: usize,
+
+    /// The number of chunks that were returned to the backing allocator,
+    /// not counting the ones that are returned when the arena is dropped.
+    pub chunks_freed: usize,
+
+    /// The number of allocations that didn't fit in the current chunk and
+    /// took the slow path, whether or not they allocated a new chunk.
+    pub slow_path_hits: usize,
+
+    /// The number of bytes that were handed back to the arena by
+    /// deallocating or shrinking its most recent allocation.
+    pub bytes_reclaimed: usize,
+
+    /// The most bytes that were in use in the arena at once, including
+    /// before it was reset or rolled back.
+    pub high_water_mark: usize,
+}
+
+// Where a `Bump` keeps its statistics. Without the `stats` feature, this is
+// empty and updating it does nothing.
+#[derive(Debug, Default)]
+pub(crate) struct StatsCell {
+    #[cfg(feature = "stats")]
+    stats: Cell<Stats>,
+}
+
+impl StatsCell {
+    #[inline(always)]
+    pub(crate) fn update(&self, f: impl FnOnce(&mut Stats)) {
+        #[cfg(feature = "stats")]
+        {
+            let mut stats = self.stats.get();
+            f(&mut stats);
+            self.stats.set(stats);
+        }
+        #[cfg(not(feature = "stats"))]
+        let _ = f;
+    }
+
+    #[cfg(feature = "stats")]
+    pub(crate) fn get(&self) -> Stats {
+        self.stats.get()
+    }
+}
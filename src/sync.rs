@@ -0,0 +1,426 @@
+//! A bump arena that can be shared between threads.
+//!
+//! See [`SyncBump`] for details.
+//!
+//! [`SyncBump`]: ../struct.SyncBump.html
+
+use crate::{
+    layout_from_size_align, oom, AllocErr, AllocErrKind, BackingAllocator, Bump, ChunkPolicy,
+    Global, CHUNK_ALIGN, FOOTER_SIZE,
+};
+use core::iter;
+use core::mem;
+use core::ptr::{self, NonNull};
+use core::str;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use core_alloc::alloc::Layout;
+use std::sync::{Mutex, PoisonError};
+
+#[cfg(feature = "allocator_api")]
+use core_alloc::alloc::{AllocError, Allocator};
+
+#[cfg(not(feature = "allocator_api"))]
+use allocator_api2::alloc::{AllocError, Allocator};
+
+/// The footer at the end of each of a `SyncBump`'s chunks.
+///
+/// Unlike a `Bump`'s chunks, only the bump finger is ever modified once the
+/// chunk is installed, and it is atomic.
+#[repr(C)]
+#[derive(Debug)]
+struct SyncChunkFooter {
+    // Pointer to the start of this chunk allocation.
+    data: NonNull<u8>,
+
+    // The layout of this chunk's allocation.
+    layout: Layout,
+
+    // Link to the previous chunk, or null if this is the oldest one.
+    prev: *mut SyncChunkFooter,
+
+    // Bump allocation finger that is always in the range `self.data..=self`,
+    // and moves down towards `self.data`.
+    ptr: AtomicPtr<u8>,
+}
+
+const _: [(); (mem::size_of::<SyncChunkFooter>() <= FOOTER_SIZE) as usize] = [()];
+
+/// A bump allocation arena that can be shared between threads.
+///
+/// A [`Bump`] is `Send`, but not `Sync`, so each thread needs an arena of its
+/// own, and values allocated by different threads can't share a lifetime. A
+/// `SyncBump` can be allocated from by many threads at once through a shared
+/// `&SyncBump`, so everything allocated in it lives as long as that one
+/// borrow.
+///
+/// Allocating from a `SyncBump` bumps the current chunk's finger with an
+/// atomic compare-and-exchange, which is slower than bumping a `Bump`'s finger,
+/// especially when many threads contend for it. When the current chunk is
+/// full, a new one is allocated and installed while holding a lock.
+///
+/// A `SyncBump` only offers the basics of `Bump`'s allocation methods:
+/// [`alloc`][SyncBump::alloc], [`alloc_slice_copy`][SyncBump::alloc_slice_copy],
+/// [`alloc_str`][SyncBump::alloc_str], and their fallible and layout-based
+/// versions. `&SyncBump` also implements the `Allocator` trait, just like
+/// `&Bump` does.
+///
+/// Like with a `Bump`, values allocated in a `SyncBump` are never dropped.
+///
+/// This type is only available when the `std` cargo feature is enabled.
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::SyncBump;
+///
+/// let bump = SyncBump::new();
+/// let strs: Vec<&str> = std::thread::scope(|s| {
+///     let handles: Vec<_> = (0..4)
+///         .map(|i| {
+///             let bump = &bump;
+///             s.spawn(move || &*bump.alloc_str(&format!("thread {}", i)))
+///         })
+///         .collect();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect()
+/// });
+/// assert_eq!(strs, ["thread 0", "thread 1", "thread 2", "thread 3"]);
+/// ```
+///
+/// [`Bump`]: struct.Bump.html
+#[derive(Debug)]
+pub struct SyncBump<A: BackingAllocator = Global> {
+    // The chunk that is currently bump allocated into, or null if there is
+    // none yet. Chunks are only deallocated when the arena is reset or
+    // dropped, so a footer that was loaded from here stays valid for as long
+    // as the arena is borrowed.
+    current_chunk_footer: AtomicPtr<SyncChunkFooter>,
+    // Held while a new chunk is allocated and installed.
+    grow_lock: Mutex<()>,
+    // The allocator that chunks are allocated from and returned to.
+    allocator: A,
+}
+
+impl SyncBump {
+    /// Construct a new, empty arena that can be shared between threads.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::SyncBump::new();
+    /// # let _ = bump;
+    /// ```
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+
+    /// Construct a new arena that can be shared between threads, with the
+    /// specified byte capacity to bump allocate into.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::SyncBump::with_capacity(100);
+    /// # let _ = bump;
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+}
+
+impl<A: BackingAllocator> SyncBump<A> {
+    /// Construct a new, empty arena that can be shared between threads, and
+    /// that allocates its chunks from `allocator`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{Global, SyncBump};
+    ///
+    /// let bump = SyncBump::new_in(Global);
+    /// # let _ = bump;
+    /// ```
+    pub fn new_in(allocator: A) -> Self {
+        SyncBump {
+            current_chunk_footer: AtomicPtr::new(ptr::null_mut()),
+            grow_lock: Mutex::new(()),
+            allocator,
+        }
+    }
+
+    /// Construct a new arena that can be shared between threads, with the
+    /// specified byte capacity to bump allocate into, and that allocates its
+    /// chunks from `allocator`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use bumpalo::{Global, SyncBump};
+    ///
+    /// let bump = SyncBump::with_capacity_in(100, Global);
+    /// # let _ = bump;
+    /// ```
+    pub fn with_capacity_in(capacity: usize, allocator: A) -> Self {
+        let bump = Self::new_in(allocator);
+        if capacity > 0 {
+            let layout = layout_from_size_align(capacity, 1).unwrap_or_else(|_| oom());
+            let footer = bump
+                .new_chunk(Some(capacity), layout, ptr::null_mut())
+                .unwrap_or_else(|| oom());
+            bump.current_chunk_footer.store(footer, Ordering::Release);
+        }
+        bump
+    }
+
+    /// Get a reference to the allocator that this arena's chunks are allocated
+    /// from.
+    pub fn allocator(&self) -> &A {
+        &self.allocator
+    }
+
+    basic_alloc_methods!("SyncBump");
+
+    /// Attempts to allocate space for an object with the given `Layout` or else returns
+    /// an `Err`.
+    ///
+    /// The returned pointer points at uninitialized memory, and should be
+    /// initialized with
+    /// [`std::ptr::write`](https://doc.rust-lang.org/std/ptr/fn.write.html).
+    ///
+    /// # Errors
+    ///
+    /// Errors if reserving space matching `layout` fails.
+    #[inline(always)]
+    pub fn try_alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        let footer = self.current_chunk_footer.load(Ordering::Acquire);
+        if let Some(p) = unsafe { Self::try_alloc_layout_in(footer, layout) } {
+            Ok(p)
+        } else {
+            self.alloc_layout_slow(layout)
+                .ok_or_else(|| AllocErr::new(AllocErrKind::Allocator, Some(layout), None))
+        }
+    }
+
+    /// Try to bump allocate `layout` in the chunk that `footer` belongs to.
+    #[inline(always)]
+    unsafe fn try_alloc_layout_in(
+        footer: *mut SyncChunkFooter,
+        layout: Layout,
+    ) -> Option<NonNull<u8>> {
+        let footer = footer.as_ref()?;
+        let start = footer.data.as_ptr() as usize;
+        let mut ptr = footer.ptr.load(Ordering::Relaxed);
+        loop {
+            let new_addr = (ptr as usize).checked_sub(layout.size())? & !(layout.align() - 1);
+            if new_addr < start {
+                return None;
+            }
+            let new_ptr = ptr.sub(ptr as usize - new_addr);
+            match footer.ptr.compare_exchange_weak(
+                ptr,
+                new_ptr,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(NonNull::new_unchecked(new_ptr)),
+                Err(current) => ptr = current,
+            }
+        }
+    }
+
+    /// Slow path allocation for when there isn't enough room in the current
+    /// chunk.
+    #[inline(never)]
+    #[cold]
+    fn alloc_layout_slow(&self, layout: Layout) -> Option<NonNull<u8>> {
+        let _guard = self
+            .grow_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // Another thread may have installed a new chunk while we were waiting
+        // for the lock.
+        let current = self.current_chunk_footer.load(Ordering::Acquire);
+        unsafe {
+            if let Some(p) = Self::try_alloc_layout_in(current, layout) {
+                return Some(p);
+            }
+
+            // Like a `Bump`, try to double the size of the chunks, and halve
+            // that until the allocator accepts it or it gets too small.
+            let policy = &ChunkPolicy::DEFAULT;
+            let min_new_chunk_size = layout.size().max(policy.min_chunk_size());
+            let mut base_size = match current.as_ref() {
+                Some(footer) => policy.next_chunk_size(footer.layout.size() - FOOTER_SIZE)?,
+                None => 0,
+            }
+            .max(min_new_chunk_size);
+            let new_footer = iter::from_fn(|| {
+                if base_size >= min_new_chunk_size {
+                    let size = base_size;
+                    base_size /= 2;
+                    Some(size)
+                } else {
+                    None
+                }
+            })
+            .find_map(|size| self.new_chunk(Some(size), layout, current))?;
+
+            // No other thread can see the new chunk yet.
+            let p = Self::try_alloc_layout_in(new_footer, layout);
+            debug_assert!(p.is_some());
+            self.current_chunk_footer
+                .store(new_footer, Ordering::Release);
+            p
+        }
+    }
+
+    /// Allocate a new chunk that fits `layout`, and return its initialized
+    /// footer.
+    fn new_chunk(
+        &self,
+        size: Option<usize>,
+        layout: Layout,
+        prev: *mut SyncChunkFooter,
+    ) -> Option<*mut SyncChunkFooter> {
+        let details = Bump::<A>::new_chunk_memory_details(size, layout, &ChunkPolicy::DEFAULT)?;
+        let chunk_layout = layout_from_size_align(details.size, details.align).ok()?;
+        let data = self.allocator.allocate(chunk_layout).ok()?.cast::<u8>();
+
+        unsafe {
+            let footer_ptr = data.as_ptr().add(details.new_size_without_footer);
+            debug_assert_eq!(footer_ptr as usize % CHUNK_ALIGN, 0);
+            let footer = footer_ptr as *mut SyncChunkFooter;
+            ptr::write(
+                footer,
+                SyncChunkFooter {
+                    data,
+                    layout: chunk_layout,
+                    prev,
+                    ptr: AtomicPtr::new(footer_ptr),
+                },
+            );
+            Some(footer)
+        }
+    }
+
+    /// Reset this arena, deallocating everything that was allocated in it.
+    ///
+    /// Does not run any `Drop` implementations on deallocated objects, just
+    /// like [`Bump::reset`]. The most recently allocated chunk is kept, and
+    /// the others are returned to the backing allocator.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut bump = bumpalo::SyncBump::new();
+    /// bump.alloc_slice_copy(&[0_u8; 10_000]);
+    /// bump.reset();
+    /// assert!(bump.allocated_bytes() < 20_000);
+    /// ```
+    ///
+    /// [`Bump::reset`]: struct.Bump.html#method.reset
+    pub fn reset(&mut self) {
+        let current = *self.current_chunk_footer.get_mut();
+        unsafe {
+            if let Some(footer) = current.as_mut() {
+                self.dealloc_chunk_list(mem::replace(&mut footer.prev, ptr::null_mut()));
+                *footer.ptr.get_mut() = current as *mut u8;
+            }
+        }
+    }
+
+    /// Get the number of bytes that this arena has allocated for its chunks,
+    /// not counting its own metadata, like [`Bump::allocated_bytes`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::SyncBump::new();
+    /// bump.alloc_slice_copy(&[0_u32; 5]);
+    /// assert!(bump.allocated_bytes() >= 20);
+    /// ```
+    ///
+    /// [`Bump::allocated_bytes`]: struct.Bump.html#method.allocated_bytes
+    pub fn allocated_bytes(&self) -> usize {
+        let mut footer = self.current_chunk_footer.load(Ordering::Acquire);
+        let mut bytes = 0;
+        while let Some(f) = unsafe { footer.as_ref() } {
+            bytes += f.layout.size() - FOOTER_SIZE;
+            footer = f.prev;
+        }
+        bytes
+    }
+
+    unsafe fn dealloc_chunk_list(&self, mut footer: *mut SyncChunkFooter) {
+        while let Some(f) = footer.as_ref() {
+            footer = f.prev;
+            self.allocator.deallocate(f.data, f.layout);
+        }
+    }
+}
+
+impl<A: BackingAllocator + Default> Default for SyncBump<A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<A: BackingAllocator> Drop for SyncBump<A> {
+    fn drop(&mut self) {
+        let current = *self.current_chunk_footer.get_mut();
+        unsafe {
+            self.dealloc_chunk_list(current);
+        }
+    }
+}
+
+// The chunks are owned by the arena, and only ever accessed through atomics
+// or while holding the lock, so sharing the arena is as safe as sharing its
+// allocator.
+unsafe impl<A: BackingAllocator + Send> Send for SyncBump<A> {}
+unsafe impl<A: BackingAllocator + Sync> Sync for SyncBump<A> {}
+
+unsafe impl<A: BackingAllocator> Allocator for &SyncBump<A> {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.try_alloc_layout(layout)
+            .map(|p| unsafe {
+                NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(p.as_ptr(), layout.size()))
+            })
+            .map_err(|_| AllocError)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // If this is still the most recent allocation in the current chunk,
+        // give its space back. Otherwise, it is only reclaimed on reset.
+        if let Some(footer) = self.current_chunk_footer.load(Ordering::Acquire).as_ref() {
+            let _ = footer.ptr.compare_exchange(
+                ptr.as_ptr(),
+                ptr.as_ptr().add(layout.size()),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+        }
+    }
+
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // Keep the allocation where it is, unless it isn't aligned enough.
+        if ptr.as_ptr() as usize % new_layout.align() == 0 {
+            return Ok(NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(
+                ptr.as_ptr(),
+                new_layout.size(),
+            )));
+        }
+        let new_ptr = self.allocate(new_layout)?;
+        ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr() as *mut u8, new_layout.size());
+        self.deallocate(ptr, old_layout);
+        Ok(new_ptr)
+    }
+}
@@ -0,0 +1,428 @@
+//! An arena that only holds values of a single type.
+//!
+//! See [`TypedBump`] for details.
+//!
+//! [`TypedBump`]: ../struct.TypedBump.html
+
+use crate::{round_up_to, Bump, ChunkFooter, UpBump};
+use core::cell::Cell;
+use core::fmt;
+use core::iter::{FlatMap, FusedIterator};
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::{self, NonNull};
+use core_alloc::vec::Vec;
+
+/// An arena that only holds values of type `T`, and can iterate over them.
+///
+/// A `TypedBump` allocates its values in chunks, just like a [`Bump`] does,
+/// but since it knows what is in them, it can safely hand out the values
+/// that were allocated in it with [`iter`][TypedBump::iter] and
+/// [`iter_mut`][TypedBump::iter_mut], in the order they were allocated in,
+/// or move them all out with [`into_vec`][TypedBump::into_vec].
+///
+/// Unlike a `Bump`, a `TypedBump` drops its values when it is dropped or
+/// [`reset`][TypedBump::reset].
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::TypedBump;
+///
+/// let mut names = TypedBump::new();
+/// let alice = names.alloc(String::from("Alice"));
+/// alice.push_str(" Smith");
+/// names.alloc(String::from("Bob"));
+///
+/// let all: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
+/// assert_eq!(all, ["Alice Smith", "Bob"]);
+/// ```
+///
+/// [`Bump`]: struct.Bump.html
+pub struct TypedBump<T> {
+    // Bumping upwards keeps each chunk's values contiguous and in allocation
+    // order, from the start of the chunk to its bump finger.
+    bump: UpBump,
+    // How many values were allocated, which is the only way to tell for
+    // zero-sized types.
+    len: Cell<usize>,
+    values: PhantomData<T>,
+}
+
+impl<T> TypedBump<T> {
+    /// Construct a new, empty typed arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let arena = bumpalo::TypedBump::<u32>::new();
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Self::from_bump(Bump::upward())
+    }
+
+    /// Construct a new typed arena with room for `capacity` values before it
+    /// needs to allocate another chunk.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let arena = bumpalo::TypedBump::<u32>::with_capacity(100);
+    /// # let _ = arena;
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        let bytes = mem::size_of::<T>()
+            .checked_mul(capacity)
+            .unwrap_or_else(|| crate::oom());
+        Self::from_bump(Bump::upward_with_capacity(bytes))
+    }
+
+    fn from_bump(bump: UpBump) -> Self {
+        // Values are found by walking each chunk, so they must be back to
+        // back, which they are since this arena has no red zones.
+        TypedBump {
+            bump,
+            len: Cell::new(0),
+            values: PhantomData,
+        }
+    }
+
+    /// Allocate a value in this arena and return an exclusive reference to
+    /// it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if reserving space for `T` fails.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let arena = bumpalo::TypedBump::new();
+    /// let x = arena.alloc(42);
+    /// *x += 1;
+    /// assert_eq!(*x, 43);
+    /// ```
+    #[inline(always)]
+    pub fn alloc(&self, value: T) -> &mut T {
+        let x = self.bump.alloc(value);
+        self.len.set(self.len.get() + 1);
+        x
+    }
+
+    /// Get the number of values that were allocated in this arena.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let arena = bumpalo::TypedBump::new();
+    /// arena.alloc('a');
+    /// arena.alloc('b');
+    /// assert_eq!(arena.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Does this arena hold no values?
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let arena = bumpalo::TypedBump::new();
+    /// assert!(arena.is_empty());
+    /// arena.alloc(());
+    /// assert!(!arena.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the values in this arena, in the order they were
+    /// allocated in.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut arena = bumpalo::TypedBump::new();
+    /// for i in 0..1000 {
+    ///     arena.alloc(i);
+    /// }
+    /// assert!(arena.iter().copied().eq(0..1000));
+    /// ```
+    pub fn iter<'a>(&'a mut self) -> TypedIter<'a, T> {
+        let as_slice: AsSlice<'a, T> =
+            |(ptr, len)| unsafe { &*ptr::slice_from_raw_parts(ptr.as_ptr(), len) };
+        TypedIter {
+            inner: self.chunks().flat_map(as_slice),
+        }
+    }
+
+    /// Iterate over exclusive references to the values in this arena, in the
+    /// order they were allocated in.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut arena = bumpalo::TypedBump::new();
+    /// arena.alloc(1);
+    /// arena.alloc(2);
+    /// for x in arena.iter_mut() {
+    ///     *x *= 10;
+    /// }
+    /// assert!(arena.iter().copied().eq([10, 20]));
+    /// ```
+    pub fn iter_mut<'a>(&'a mut self) -> TypedIterMut<'a, T> {
+        let as_slice: AsMutSlice<'a, T> =
+            |(ptr, len)| unsafe { &mut *ptr::slice_from_raw_parts_mut(ptr.as_ptr(), len) };
+        TypedIterMut {
+            inner: self.chunks().flat_map(as_slice),
+        }
+    }
+
+    /// Move every value out of this arena and into a `Vec`, in the order they
+    /// were allocated in.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let arena = bumpalo::TypedBump::new();
+    /// arena.alloc(String::from("a"));
+    /// arena.alloc(String::from("b"));
+    /// assert_eq!(arena.into_vec(), ["a", "b"]);
+    /// ```
+    pub fn into_vec(mut self) -> Vec<T> {
+        let mut values = Vec::<T>::with_capacity(self.len());
+        for (ptr, len) in self.chunks() {
+            unsafe {
+                ptr::copy_nonoverlapping(ptr.as_ptr(), values.as_mut_ptr().add(values.len()), len);
+                values.set_len(values.len() + len);
+            }
+        }
+        debug_assert_eq!(values.len(), self.len());
+        // The values were moved out, so forget about them.
+        self.bump.reset();
+        self.len.set(0);
+        values
+    }
+
+    /// Drop every value in this arena, and then reset it, as [`Bump::reset`]
+    /// does.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut arena = bumpalo::TypedBump::new();
+    /// arena.alloc(vec![1, 2, 3]);
+    /// arena.reset();
+    /// assert!(arena.is_empty());
+    /// ```
+    ///
+    /// [`Bump::reset`]: struct.Bump.html#method.reset
+    pub fn reset(&mut self) {
+        self.drop_values();
+        self.bump.reset();
+        self.len.set(0);
+    }
+
+    /// The start and length of the values in each chunk, oldest chunk first.
+    fn chunks(&mut self) -> Chunks<'_, T> {
+        if mem::size_of::<T>() == 0 {
+            return Chunks {
+                ends: None,
+                zst_len: Some(self.len.get()),
+                values: PhantomData,
+            };
+        }
+        let newest = self.bump.current_chunk_footer.get();
+        let oldest = self
+            .bump
+            .iter_footers()
+            .take_while(|footer| unsafe { !footer.as_ref().is_empty() })
+            .last();
+        Chunks {
+            ends: oldest.map(|oldest| (oldest, newest)),
+            zst_len: None,
+            values: PhantomData,
+        }
+    }
+
+    fn drop_values(&mut self) {
+        if mem::needs_drop::<T>() {
+            for (ptr, len) in self.chunks() {
+                unsafe {
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(ptr.as_ptr(), len));
+                }
+            }
+        }
+    }
+}
+
+/// The start and length of the values in each of a `TypedBump`'s chunks,
+/// oldest chunk first.
+///
+/// Chunks only link to the chunk before them, so going forwards walks down
+/// from the newest chunk that is left to find the one after the last chunk
+/// that was returned. Chunks grow geometrically, so there are few of them,
+/// and this doesn't need to allocate a list of them.
+#[derive(Debug)]
+struct Chunks<'a, T> {
+    // The oldest and newest chunks that weren't returned yet, if any.
+    ends: Option<(NonNull<ChunkFooter>, NonNull<ChunkFooter>)>,
+    // For zero-sized types, which don't take up any room in the chunks, the
+    // number of values, until it is returned.
+    zst_len: Option<usize>,
+    values: PhantomData<&'a [T]>,
+}
+
+impl<T> Chunks<'_, T> {
+    fn values_in(footer: NonNull<ChunkFooter>) -> (NonNull<T>, usize) {
+        let footer = unsafe { footer.as_ref() };
+        let (data, used) = footer.as_raw_parts(true);
+        // The first value in each chunk is at the start of the chunk, unless
+        // the chunk is less aligned than the values.
+        let start = round_up_to(data as usize, mem::align_of::<T>()).unwrap();
+        let end = data as usize + used;
+        if end > start {
+            let ptr = data.wrapping_add(start - data as usize) as *mut T;
+            let len = (end - start) / mem::size_of::<T>();
+            (unsafe { NonNull::new_unchecked(ptr) }, len)
+        } else {
+            (NonNull::dangling(), 0)
+        }
+    }
+}
+
+impl<T> Iterator for Chunks<'_, T> {
+    type Item = (NonNull<T>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(len) = self.zst_len.take() {
+            return Some((NonNull::dangling(), len));
+        }
+        let (oldest, newest) = self.ends?;
+        self.ends = if oldest == newest {
+            None
+        } else {
+            let mut next = newest;
+            loop {
+                let prev = unsafe { next.as_ref().prev.get() };
+                if prev == oldest {
+                    break;
+                }
+                next = prev;
+            }
+            Some((next, newest))
+        };
+        Some(Self::values_in(oldest))
+    }
+}
+
+impl<T> DoubleEndedIterator for Chunks<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(len) = self.zst_len.take() {
+            return Some((NonNull::dangling(), len));
+        }
+        let (oldest, newest) = self.ends?;
+        self.ends = if oldest == newest {
+            None
+        } else {
+            Some((oldest, unsafe { newest.as_ref().prev.get() }))
+        };
+        Some(Self::values_in(newest))
+    }
+}
+
+type AsSlice<'a, T> = fn((NonNull<T>, usize)) -> &'a [T];
+type AsMutSlice<'a, T> = fn((NonNull<T>, usize)) -> &'a mut [T];
+
+impl<T> Default for TypedBump<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TypedBump<T> {
+    fn drop(&mut self) {
+        self.drop_values();
+    }
+}
+
+impl<T> fmt::Debug for TypedBump<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedBump")
+            .field("bump", &self.bump)
+            .field("len", &self.len.get())
+            .finish()
+    }
+}
+
+/// An iterator over the values in a [`TypedBump`], in the order they were
+/// allocated in.
+///
+/// This struct is created by the [`iter`] method on [`TypedBump`].
+///
+/// [`TypedBump`]: struct.TypedBump.html
+/// [`iter`]: struct.TypedBump.html#method.iter
+#[derive(Debug)]
+pub struct TypedIter<'a, T> {
+    inner: FlatMap<Chunks<'a, T>, &'a [T], AsSlice<'a, T>>,
+}
+
+impl<'a, T> Iterator for TypedIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for TypedIter<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> FusedIterator for TypedIter<'_, T> {}
+
+/// An iterator over exclusive references to the values in a [`TypedBump`],
+/// in the order they were allocated in.
+///
+/// This struct is created by the [`iter_mut`] method on [`TypedBump`].
+///
+/// [`TypedBump`]: struct.TypedBump.html
+/// [`iter_mut`]: struct.TypedBump.html#method.iter_mut
+#[derive(Debug)]
+pub struct TypedIterMut<'a, T> {
+    inner: FlatMap<Chunks<'a, T>, &'a mut [T], AsMutSlice<'a, T>>,
+}
+
+impl<'a, T> Iterator for TypedIterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for TypedIterMut<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> FusedIterator for TypedIterMut<'_, T> {}
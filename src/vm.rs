@@ -0,0 +1,474 @@
+//! An arena that bump allocates into one contiguous range of virtual memory.
+//!
+//! See [`VmBump`] for details.
+//!
+//! [`VmBump`]: ../struct.VmBump.html
+
+use crate::{oom, round_up_to, AllocErr, AllocErrKind};
+use core::cell::Cell;
+use core::iter;
+use core::mem::MaybeUninit;
+use core::ptr::{self, NonNull};
+use core::slice;
+use core::str;
+use core_alloc::alloc::Layout;
+
+#[cfg(feature = "allocator_api")]
+use core_alloc::alloc::{AllocError, Allocator};
+
+#[cfg(not(feature = "allocator_api"))]
+use allocator_api2::alloc::{AllocError, Allocator};
+
+/// How much address space [`VmBump::new`] reserves.
+#[cfg(target_pointer_width = "64")]
+const DEFAULT_RESERVATION: usize = 1 << 32;
+#[cfg(not(target_pointer_width = "64"))]
+const DEFAULT_RESERVATION: usize = 1 << 28;
+
+/// The size of a transparent huge page. Reservations are aligned to it and
+/// are a multiple of it, so that huge pages can back all of them.
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+/// The least that is committed at once, so that advancing the bump finger
+/// doesn't need a system call for every page. This is a multiple of the page
+/// size on every architecture that Linux supports.
+const MIN_COMMIT: usize = 64 * 1024;
+
+/// A bump allocation arena that reserves one large range of virtual memory up
+/// front, and bump allocates into it from start to end.
+///
+/// A [`Bump`] allocates a new chunk whenever its current one is full, so what
+/// it allocated is spread over many chunks. A `VmBump` instead reserves
+/// address space without backing it with memory, and commits pages of it as
+/// its bump finger advances. Everything allocated in a `VmBump` is therefore
+/// contiguous: [`iter_allocated_chunks`][VmBump::iter_allocated_chunks]
+/// returns a single slice, and growing the most recent allocation through the
+/// `Allocator` trait never needs to copy it. Allocating fails with
+/// [`AllocErrKind::Allocator`] once the reservation is full.
+///
+/// [`reset`][VmBump::reset] gives the committed pages' memory back to the
+/// operating system, which zeroes them the next time they are used.
+/// [`set_huge_pages`][VmBump::set_huge_pages] asks for the reservation to be
+/// backed by transparent huge pages.
+///
+/// Of `Bump`'s allocation methods, a `VmBump` has the same ones that a
+/// [`SyncBump`] has: [`alloc`][VmBump::alloc],
+/// [`alloc_slice_copy`][VmBump::alloc_slice_copy], [`alloc_str`][VmBump::alloc_str],
+/// and their fallible and layout-based versions. Everything else, including
+/// the `collections` and `boxed` types that need a `&Bump`, can go through
+/// `&VmBump`'s `Allocator` implementation instead.
+///
+/// Like with a `Bump`, values allocated in a `VmBump` are never dropped.
+///
+/// This type is only available on Linux, with the `std` cargo feature.
+///
+/// ## Example
+///
+/// ```
+/// use bumpalo::VmBump;
+///
+/// let mut bump = VmBump::new();
+/// for i in 0..100_000_u64 {
+///     bump.alloc(i);
+/// }
+///
+/// // Everything is in one slice, no matter how much was allocated.
+/// let chunks: Vec<_> = bump.iter_allocated_chunks().collect();
+/// assert_eq!(chunks.len(), 1);
+/// assert_eq!(chunks[0].len(), 800_000);
+/// ```
+///
+/// [`Bump`]: struct.Bump.html
+/// [`SyncBump`]: struct.SyncBump.html
+/// [`AllocErrKind::Allocator`]: enum.AllocErrKind.html#variant.Allocator
+#[derive(Debug)]
+pub struct VmBump {
+    // The start of the reserved range.
+    base: NonNull<u8>,
+    // The size of the reserved range.
+    reserved: usize,
+    // How much of the reserved range, from its start, is readable and
+    // writable.
+    committed: Cell<usize>,
+    // The bump finger, as an offset from `base`. It only moves upwards.
+    used: Cell<usize>,
+    // Whether huge pages were asked for.
+    huge_pages: Cell<bool>,
+}
+
+impl VmBump {
+    /// Construct a new, empty arena that reserves 4 GiB of address space, or
+    /// 256 MiB on 32-bit platforms.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the address space can't be reserved.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::VmBump::new();
+    /// # let _ = bump;
+    /// ```
+    pub fn new() -> Self {
+        Self::with_reservation(DEFAULT_RESERVATION)
+    }
+
+    /// Construct a new, empty arena that reserves at least `bytes` of address
+    /// space.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the address space can't be reserved.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::VmBump::with_reservation(1 << 20);
+    /// assert!(bump.reserved_bytes() >= 1 << 20);
+    /// ```
+    pub fn with_reservation(bytes: usize) -> Self {
+        Self::try_with_reservation(bytes).unwrap_or_else(|_| oom())
+    }
+
+    /// Attempt to construct a new, empty arena that reserves at least `bytes`
+    /// of address space.
+    ///
+    /// ## Errors
+    ///
+    /// Errors if the address space can't be reserved.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::VmBump::try_with_reservation(1 << 20);
+    /// # let _ = bump.unwrap();
+    /// ```
+    pub fn try_with_reservation(bytes: usize) -> Result<Self, AllocErr> {
+        let layout = Layout::from_size_align(bytes, 1).ok();
+        let reserved = round_up_to(bytes.max(1), HUGE_PAGE_SIZE)
+            .ok_or_else(|| AllocErr::new(AllocErrKind::SizeOverflow, layout, None))?;
+        let base = reserve(reserved)
+            .ok_or_else(|| AllocErr::new(AllocErrKind::Allocator, layout, None))?;
+        Ok(VmBump {
+            base,
+            reserved,
+            committed: Cell::new(0),
+            used: Cell::new(0),
+            huge_pages: Cell::new(false),
+        })
+    }
+
+    /// Ask for this arena's memory to be backed by transparent huge pages, or
+    /// stop asking for it.
+    ///
+    /// Huge pages make accessing a large arena cheaper, but commit its memory
+    /// in bigger steps. This is only a hint, which the kernel may ignore,
+    /// for example when transparent huge pages are disabled.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::VmBump::new();
+    /// bump.set_huge_pages(true);
+    /// bump.alloc_slice_copy(&[0_u8; 10_000]);
+    /// ```
+    pub fn set_huge_pages(&self, enabled: bool) {
+        let advice = if enabled {
+            libc::MADV_HUGEPAGE
+        } else {
+            libc::MADV_NOHUGEPAGE
+        };
+        unsafe {
+            libc::madvise(self.base.as_ptr().cast(), self.reserved, advice);
+        }
+        self.huge_pages.set(enabled);
+    }
+
+    /// Get the number of bytes of address space that this arena reserved.
+    pub fn reserved_bytes(&self) -> usize {
+        self.reserved
+    }
+
+    /// Get the number of bytes of this arena's reservation that are committed
+    /// to be bump allocated into.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::VmBump::new();
+    /// assert_eq!(bump.allocated_bytes(), 0);
+    /// bump.alloc_slice_copy(&[0_u8; 10_000]);
+    /// assert!(bump.allocated_bytes() >= 10_000);
+    /// ```
+    pub fn allocated_bytes(&self) -> usize {
+        self.committed.get()
+    }
+
+    /// Get the number of bytes that have been bump allocated in this arena,
+    /// including padding for alignment.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let bump = bumpalo::VmBump::new();
+    /// bump.alloc(1_u8);
+    /// bump.alloc(2_u32);
+    /// assert_eq!(bump.used_bytes(), 8);
+    /// ```
+    pub fn used_bytes(&self) -> usize {
+        self.used.get()
+    }
+
+    basic_alloc_methods!("VmBump");
+
+    /// Attempts to allocate space for an object with the given `Layout` or else returns
+    /// an `Err`.
+    ///
+    /// The returned pointer points at uninitialized memory, and should be
+    /// initialized with
+    /// [`std::ptr::write`](https://doc.rust-lang.org/std/ptr/fn.write.html).
+    ///
+    /// # Errors
+    ///
+    /// Errors if reserving space matching `layout` fails.
+    #[inline(always)]
+    pub fn try_alloc_layout(&self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        let base = self.base.as_ptr() as usize;
+        let start = round_up_to(base + self.used.get(), layout.align())
+            .map(|addr| addr - base)
+            .and_then(|start| Some((start, start.checked_add(layout.size())?)));
+        let (start, end) = match start {
+            Some(range) => range,
+            None => {
+                return Err(AllocErr::new(
+                    AllocErrKind::SizeOverflow,
+                    Some(layout),
+                    None,
+                ))
+            }
+        };
+        if end > self.committed.get() {
+            self.commit(end)
+                .map_err(|kind| AllocErr::new(kind, Some(layout), None))?;
+        }
+        self.used.set(end);
+        unsafe { Ok(NonNull::new_unchecked(self.base.as_ptr().add(start))) }
+    }
+
+    /// Commit enough of the reservation for the bump finger to advance to
+    /// `end`.
+    #[inline(never)]
+    #[cold]
+    fn commit(&self, end: usize) -> Result<(), AllocErrKind> {
+        if end > self.reserved {
+            return Err(AllocErrKind::Allocator);
+        }
+        let step = if self.huge_pages.get() {
+            HUGE_PAGE_SIZE
+        } else {
+            MIN_COMMIT
+        };
+        // The reservation is a multiple of the step, so this stays aligned.
+        let new_committed = round_up_to(end, step).map_or(self.reserved, |c| c.min(self.reserved));
+        let committed = self.committed.get();
+        let result = unsafe {
+            libc::mprotect(
+                self.base.as_ptr().add(committed).cast(),
+                new_committed - committed,
+                libc::PROT_READ | libc::PROT_WRITE,
+            )
+        };
+        if result != 0 {
+            return Err(AllocErrKind::Allocator);
+        }
+        self.committed.set(new_committed);
+        Ok(())
+    }
+
+    /// Reset this arena, deallocating everything that was allocated in it.
+    ///
+    /// Does not run any `Drop` implementations on deallocated objects, just
+    /// like [`Bump::reset`]. The committed pages stay committed, but their
+    /// memory is given back to the operating system, and they read as zeroes
+    /// when they are used again.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut bump = bumpalo::VmBump::new();
+    /// bump.alloc_slice_copy(&[1_u8; 10_000]);
+    /// bump.reset();
+    /// assert_eq!(bump.used_bytes(), 0);
+    /// ```
+    ///
+    /// [`Bump::reset`]: struct.Bump.html#method.reset
+    pub fn reset(&mut self) {
+        let committed = self.committed.get();
+        if committed > 0 {
+            unsafe {
+                libc::madvise(self.base.as_ptr().cast(), committed, libc::MADV_DONTNEED);
+            }
+        }
+        self.used.set(0);
+    }
+
+    /// Returns an iterator over the memory that this arena has bump allocated
+    /// into, like [`Bump::iter_allocated_chunks`] does.
+    ///
+    /// Because a `VmBump`'s allocations are contiguous, the iterator returns
+    /// a single slice, with the least recent allocation first. There could be
+    /// uninitialized padding between the allocations, which is why the slice
+    /// has items of type `MaybeUninit<u8>`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// let mut bump = bumpalo::VmBump::new();
+    /// bump.alloc_str("hello");
+    /// bump.alloc_str("world");
+    ///
+    /// let chunk = bump.iter_allocated_chunks().next().unwrap();
+    /// let bytes: Vec<u8> = chunk.iter().map(|b| unsafe { b.assume_init() }).collect();
+    /// assert_eq!(bytes, b"helloworld");
+    /// ```
+    ///
+    /// [`Bump::iter_allocated_chunks`]: struct.Bump.html#method.iter_allocated_chunks
+    pub fn iter_allocated_chunks(&mut self) -> iter::Once<&[MaybeUninit<u8>]> {
+        let chunk = unsafe {
+            slice::from_raw_parts(
+                self.base.as_ptr().cast::<MaybeUninit<u8>>(),
+                self.used.get(),
+            )
+        };
+        iter::once(chunk)
+    }
+
+    // Whether the allocation of `size` bytes at `ptr` is the most recent one,
+    // and ends at the bump finger.
+    fn is_last_allocation(&self, ptr: NonNull<u8>, size: usize) -> bool {
+        ptr.as_ptr() as usize + size == self.base.as_ptr() as usize + self.used.get()
+    }
+
+    // The offset of `ptr` from the start of the reservation.
+    fn offset_of(&self, ptr: NonNull<u8>) -> usize {
+        ptr.as_ptr() as usize - self.base.as_ptr() as usize
+    }
+}
+
+impl Default for VmBump {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for VmBump {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.base.as_ptr().cast(), self.reserved);
+        }
+    }
+}
+
+// The reservation is owned by the arena, like a `Bump` owns its chunks.
+unsafe impl Send for VmBump {}
+
+/// Reserve `size` bytes of address space, aligned to `HUGE_PAGE_SIZE`,
+/// without committing any of it.
+fn reserve(size: usize) -> Option<NonNull<u8>> {
+    // Reserve more than needed, and trim it to be aligned.
+    let padded = size.checked_add(HUGE_PAGE_SIZE)?;
+    unsafe {
+        let p = libc::mmap(
+            ptr::null_mut(),
+            padded,
+            libc::PROT_NONE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_NORESERVE,
+            -1,
+            0,
+        );
+        if p == libc::MAP_FAILED {
+            return None;
+        }
+        let p = p.cast::<u8>();
+        let head = (p as usize).wrapping_neg() & (HUGE_PAGE_SIZE - 1);
+        let tail = padded - head - size;
+        if head > 0 {
+            libc::munmap(p.cast(), head);
+        }
+        if tail > 0 {
+            libc::munmap(p.add(head + size).cast(), tail);
+        }
+        NonNull::new(p.add(head))
+    }
+}
+
+unsafe impl Allocator for &VmBump {
+    #[inline]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.try_alloc_layout(layout)
+            .map(|p| unsafe {
+                NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(p.as_ptr(), layout.size()))
+            })
+            .map_err(|_| AllocError)
+    }
+
+    #[inline]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        // If this is the most recent allocation, give its space back.
+        // Otherwise, it is only reclaimed on reset.
+        if self.is_last_allocation(ptr, layout.size()) {
+            self.used.set(self.offset_of(ptr));
+        }
+    }
+
+    #[inline]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // Keep the allocation where it is, unless it isn't aligned enough.
+        if ptr.as_ptr() as usize % new_layout.align() != 0 {
+            let new_ptr = self.allocate(new_layout)?;
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr().cast(), new_layout.size());
+            return Ok(new_ptr);
+        }
+        if self.is_last_allocation(ptr, old_layout.size()) {
+            self.used.set(self.offset_of(ptr) + new_layout.size());
+        }
+        Ok(NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(
+            ptr.as_ptr(),
+            new_layout.size(),
+        )))
+    }
+
+    #[inline]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        // The most recent allocation ends at the bump finger, so it can
+        // always grow in place, as long as the reservation has room.
+        if self.is_last_allocation(ptr, old_layout.size())
+            && ptr.as_ptr() as usize % new_layout.align() == 0
+        {
+            let end = self.offset_of(ptr) + new_layout.size();
+            if end > self.committed.get() {
+                self.commit(end).map_err(|_| AllocError)?;
+            }
+            self.used.set(end);
+            return Ok(NonNull::new_unchecked(ptr::slice_from_raw_parts_mut(
+                ptr.as_ptr(),
+                new_layout.size(),
+            )));
+        }
+
+        let new_ptr = self.allocate(new_layout)?;
+        ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr().cast(), old_layout.size());
+        Ok(new_ptr)
+    }
+}
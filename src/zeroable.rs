@@ -0,0 +1,111 @@
+//! Types that can be allocated zeroed, without running any code to initialize
+//! them.
+//!
+//! See [`Zeroable`] for details.
+//!
+//! [`Zeroable`]: ../trait.Zeroable.html
+
+use core::cell::{Cell, UnsafeCell};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+};
+use core::ptr::NonNull;
+
+/// Types for which a value whose bytes are all zero is valid.
+///
+/// This is what [`Bump::alloc_zeroed_slice`] needs to hand out zeroed memory
+/// as initialized values. It is implemented for the primitive numeric types,
+/// `bool`, `char`, raw pointers, and for arrays and tuples of other `Zeroable`
+/// types, among others.
+///
+/// ## Safety
+///
+/// Implementing this trait for a type asserts that the all-zero bit pattern is
+/// a valid value of it. For example, it must not be implemented for
+/// references, `NonNull<T>`, or enums without a variant whose discriminant is
+/// zero.
+///
+/// A struct can implement it when all of its fields do:
+///
+/// ```
+/// use bumpalo::{Bump, Zeroable};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// unsafe impl Zeroable for Point {}
+///
+/// let bump = Bump::new();
+/// let points = bump.alloc_zeroed_slice::<Point>(3);
+/// assert_eq!(points[2], Point { x: 0.0, y: 0.0 });
+/// ```
+///
+/// [`Bump::alloc_zeroed_slice`]: ./struct.Bump.html#method.alloc_zeroed_slice
+pub unsafe trait Zeroable {}
+
+macro_rules! impl_zeroable {
+    ($($ty:ty),* $(,)?) => {
+        $(unsafe impl Zeroable for $ty {})*
+    };
+}
+
+impl_zeroable!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    Option<NonZeroU8>,
+    Option<NonZeroU16>,
+    Option<NonZeroU32>,
+    Option<NonZeroU64>,
+    Option<NonZeroU128>,
+    Option<NonZeroUsize>,
+    Option<NonZeroI8>,
+    Option<NonZeroI16>,
+    Option<NonZeroI32>,
+    Option<NonZeroI64>,
+    Option<NonZeroI128>,
+    Option<NonZeroIsize>,
+);
+
+unsafe impl<T> Zeroable for *const T {}
+unsafe impl<T> Zeroable for *mut T {}
+unsafe impl<T> Zeroable for Option<NonNull<T>> {}
+unsafe impl<T: ?Sized> Zeroable for PhantomData<T> {}
+unsafe impl<T> Zeroable for MaybeUninit<T> {}
+unsafe impl<T: Zeroable> Zeroable for Wrapping<T> {}
+unsafe impl<T: Zeroable> Zeroable for Cell<T> {}
+unsafe impl<T: Zeroable> Zeroable for UnsafeCell<T> {}
+unsafe impl<T: Zeroable, const N: usize> Zeroable for [T; N] {}
+
+macro_rules! impl_zeroable_for_tuples {
+    ($($name:ident),+) => {
+        unsafe impl<$($name: Zeroable),+> Zeroable for ($($name,)+) {}
+    };
+}
+
+impl_zeroable_for_tuples!(A);
+impl_zeroable_for_tuples!(A, B);
+impl_zeroable_for_tuples!(A, B, C);
+impl_zeroable_for_tuples!(A, B, C, D);
+impl_zeroable_for_tuples!(A, B, C, D, E);
+impl_zeroable_for_tuples!(A, B, C, D, E, F);
@@ -0,0 +1,131 @@
+use bumpalo::{Bump, Global};
+use std::cell::Cell;
+use std::fmt;
+
+// Writes its contents one piece at a time.
+struct Pieces(&'static str, usize);
+
+impl fmt::Display for Pieces {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for _ in 0..self.1 {
+            f.write_str(self.0)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn formats_like_std() {
+    let bump = Bump::new();
+    let up = Bump::upward();
+    let x = 42;
+    let name = "bump";
+    for (s, expected) in [
+        (bump.alloc_fmt(format_args!("")), String::new()),
+        (
+            bump.alloc_fmt(format_args!("literal")),
+            "literal".to_string(),
+        ),
+        (
+            bump.alloc_fmt(format_args!("{name}: {x:#06x} {:?}", [1.5, 2.0])),
+            format!("{name}: {x:#06x} {:?}", [1.5, 2.0]),
+        ),
+        (
+            up.alloc_fmt(format_args!("{} {}", Pieces("ab", 1000), "ü")),
+            format!("{} {}", Pieces("ab", 1000), "ü"),
+        ),
+        (
+            bump.alloc_fmt(format_args!("{}", Pieces("xyz", 100_000))),
+            "xyz".repeat(100_000),
+        ),
+    ] {
+        assert_eq!(s, &expected[..]);
+    }
+}
+
+#[test]
+fn strings_grow_in_place() {
+    let up = Bump::upward_with_capacity(1 << 16);
+    let before = up.chunk_capacity();
+    let s = up.alloc_fmt(format_args!("{}", Pieces("abc", 1000)));
+    let end = s.as_ptr() as usize + s.len();
+    assert_eq!(s.len(), 3000);
+    assert_eq!(before - up.chunk_capacity(), 3000);
+    assert_eq!(up.alloc(0_u8) as *mut u8 as usize, end);
+
+    let bump = Bump::with_capacity(1 << 16);
+    let before = bump.chunk_capacity();
+    let s = bump.alloc_fmt(format_args!("{}", Pieces("abc", 1000)));
+    assert_eq!(s.len(), 3000);
+    assert!(before - bump.chunk_capacity() < 2 * 3000);
+}
+
+// Writes `n` bytes one at a time into a string in `bump`, counting how many
+// times the string had to grow.
+struct CountGrowths<'a, const UP: bool> {
+    bump: &'a Bump<Global, 1, UP>,
+    n: usize,
+    growths: Cell<usize>,
+}
+
+impl<const UP: bool> fmt::Display for CountGrowths<'_, UP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut capacity = self.bump.chunk_capacity();
+        for _ in 0..self.n {
+            f.write_str("x")?;
+            if self.bump.chunk_capacity() != capacity {
+                capacity = self.bump.chunk_capacity();
+                self.growths.set(self.growths.get() + 1);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn count_growths<const UP: bool>(bump: &Bump<Global, 1, UP>, n: usize) -> usize {
+    let writer = CountGrowths {
+        bump,
+        n,
+        growths: Cell::new(0),
+    };
+    assert_eq!(bump.alloc_fmt(format_args!("{}", writer)).len(), n);
+    writer.growths.get()
+}
+
+#[test]
+fn long_strings_take_linear_time_and_space() {
+    // The capacity doubles as the string grows, so it is moved or copied a
+    // logarithmic number of times, and the buffers left behind in full chunks
+    // add up to less than the string itself.
+    const N: usize = 1 << 20;
+    let bump = Bump::new();
+    assert!(count_growths(&bump, N) <= 64);
+    assert!(bump.allocated_bytes() <= 8 * N);
+
+    let up = Bump::upward();
+    assert!(count_growths(&up, N) <= 64);
+    assert!(up.allocated_bytes() <= 8 * N);
+}
+
+#[test]
+fn strings_can_outgrow_their_chunk() {
+    let bump = Bump::with_capacity(1000);
+    let s = bump.alloc_fmt(format_args!("{}", Pieces("0123456789", 1000)));
+    assert_eq!(s.len(), 10_000);
+    assert!(s.as_bytes().chunks(10).all(|c| c == b"0123456789"));
+}
+
+#[test]
+#[should_panic(expected = "a formatting trait implementation returned an error")]
+fn formatting_errors_panic() {
+    struct Fails;
+
+    impl fmt::Display for Fails {
+        fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let bump = Bump::new();
+    let _ = bump.try_alloc_fmt(format_args!("{}", Fails));
+}
@@ -0,0 +1,113 @@
+use bumpalo::{Bump, Global};
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+#[test]
+fn collects_iterators_of_unknown_length() {
+    let bump = Bump::new();
+    let up = Bump::upward();
+    for n in [0, 1, 5, 1000, 100_000] {
+        let expected: Vec<u32> = (0..n).filter(|i| i % 3 != 0).collect();
+        let xs = bump.alloc_slice_from_iter((0..n).filter(|i| i % 3 != 0));
+        assert_eq!(xs, &expected[..]);
+        let xs = up.alloc_slice_from_iter((0..n).filter(|i| i % 3 != 0));
+        assert_eq!(xs, &expected[..]);
+    }
+
+    let words = ["hello", "", "bump", "arena"];
+    let chars = bump.alloc_slice_from_iter(words.iter().flat_map(|w| w.chars()));
+    assert_eq!(chars.iter().collect::<String>(), "hellobumparena");
+
+    let strings = bump.alloc_slice_from_iter(words.iter().map(|w| w.to_string()));
+    assert_eq!(strings, words);
+}
+
+#[test]
+fn zero_sized_elements() {
+    let bump = Bump::new();
+    let xs = bump.alloc_slice_from_iter((0..10).filter(|i| i % 2 == 0).map(|_| ()));
+    assert_eq!(xs.len(), 5);
+}
+
+#[test]
+fn iterator_can_allocate_in_the_same_bump() {
+    let bump = Bump::new();
+    let xs = bump.alloc_slice_from_iter((0..1000_u64).filter(|i| *bump.alloc(*i) % 2 == 0));
+    assert_eq!(xs.len(), 500);
+    assert!(xs.iter().enumerate().all(|(i, &x)| x == 2 * i as u64));
+}
+
+#[test]
+fn grows_without_leaving_buffers_behind() {
+    // Downward, the elements are moved down as the slice grows, and its old
+    // space is reused. Growing by copying to a new place each time would use
+    // up about twice as much of the chunk.
+    let bump = Bump::with_capacity(1 << 16);
+    let before = bump.chunk_capacity();
+    let xs = bump.alloc_slice_from_iter((0..1000_u32).filter(|_| true));
+    assert_eq!(xs.len(), 1000);
+    assert_eq!(before - bump.chunk_capacity(), 1024 * 4);
+
+    // Upward, the unused space at the end is given back, and the next
+    // allocation comes right after the slice.
+    let up = Bump::upward_with_capacity(1 << 16);
+    let before = up.chunk_capacity();
+    let xs = up.alloc_slice_from_iter((0..1000_u32).filter(|_| true));
+    let end = xs.as_ptr_range().end as usize;
+    assert_eq!(before - up.chunk_capacity(), 1000 * 4);
+    assert_eq!(up.alloc(0_u8) as *mut u8 as usize, end);
+}
+
+// Collect `n` bytes from an iterator with no size hint, and return how many
+// times the slice had to grow.
+fn count_growths<const UP: bool>(bump: &Bump<Global, 1, UP>, n: usize) -> usize {
+    let growths = Cell::new(0);
+    let capacity = Cell::new(bump.chunk_capacity());
+    let xs = bump.alloc_slice_from_iter((0..n).map(|i| i as u8).filter(|_| true).inspect(|_| {
+        if bump.chunk_capacity() != capacity.get() {
+            capacity.set(bump.chunk_capacity());
+            growths.set(growths.get() + 1);
+        }
+    }));
+    assert_eq!(xs.len(), n);
+    growths.get()
+}
+
+#[test]
+fn long_iterators_take_linear_time_and_space() {
+    // The capacity doubles as the slice grows, so it is moved or copied a
+    // logarithmic number of times, the buffers left behind in full chunks
+    // add up to less than the slice itself, and the chunks that it outgrows
+    // double in size along with it.
+    const N: usize = 1 << 20;
+    let bump = Bump::new();
+    assert!(count_growths(&bump, N) <= 64);
+    assert!(bump.allocated_bytes() <= 8 * N);
+
+    let up = Bump::upward();
+    assert!(count_growths(&up, N) <= 64);
+    assert!(up.allocated_bytes() <= 8 * N);
+}
+
+#[test]
+fn exact_size_hints_are_used() {
+    let bump = Bump::with_capacity(1 << 16);
+    let before = bump.chunk_capacity();
+    bump.alloc_slice_from_iter(0..1000_u32);
+    assert!(before - bump.chunk_capacity() < 1024 * 4);
+}
+
+#[test]
+fn elements_are_dropped_if_the_iterator_panics() {
+    let bump = Bump::new();
+    let value = Rc::new(());
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        bump.alloc_slice_from_iter((0..100).filter(|_| true).map(|i| {
+            assert!(i < 50);
+            value.clone()
+        }));
+    }));
+    assert!(result.is_err());
+    assert_eq!(Rc::strong_count(&value), 1);
+}
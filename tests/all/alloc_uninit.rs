@@ -0,0 +1,129 @@
+use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+use bumpalo::{Bump, RetainPolicy};
+use std::cell::Cell;
+use std::mem::MaybeUninit;
+use std::ptr::{addr_of_mut, NonNull};
+
+/// An allocator that counts how many chunks were requested zeroed, and hands
+/// out the others filled with garbage.
+#[derive(Default)]
+struct Zeroing {
+    zeroed: Cell<usize>,
+}
+
+unsafe impl Allocator for Zeroing {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let p = Global.allocate(layout)?;
+        unsafe { p.cast::<u8>().as_ptr().write_bytes(0xAB, layout.size()) };
+        Ok(p)
+    }
+
+    fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.zeroed.set(self.zeroed.get() + 1);
+        Global.allocate_zeroed(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn zeroed_slices_are_zeroed() {
+    let zeroing = Zeroing::default();
+    let mut bump = Bump::new_in(&zeroing);
+
+    // In a fresh chunk.
+    assert!(bump.alloc_zeroed_slice::<u32>(1000).iter().all(|&x| x == 0));
+    assert_eq!(zeroing.zeroed.get(), 1);
+
+    // In the rest of a chunk that wasn't allocated zeroed.
+    bump.alloc(1_u8);
+    assert!(bump.alloc_zeroed_slice::<u8>(8).iter().all(|&x| x == 0));
+    assert_eq!(zeroing.zeroed.get(), 1);
+
+    // In memory that was used before.
+    bump.alloc_slice_fill_copy(100_000, 0xFF_u8);
+    bump.reset_with(RetainPolicy::All);
+    let xs = bump.try_alloc_zeroed_slice::<u64>(10_000).unwrap();
+    assert!(xs.iter().all(|&x| x == 0));
+
+    bump.reset();
+    bump.alloc_slice_fill_copy(1000, 0xFF_u8);
+    bump.reset();
+    let xs = bump.alloc_zeroed_slice::<(u8, Option<NonNull<u8>>, [f32; 3])>(100);
+    assert!(xs.iter().all(|x| *x == (0, None, [0.0; 3])));
+}
+
+#[test]
+fn zeroed_slices_can_be_empty() {
+    let bump = Bump::new();
+    assert!(bump.alloc_zeroed_slice::<u64>(0).is_empty());
+    assert!(bump.alloc_zeroed_slice::<()>(10).len() == 10);
+}
+
+#[test]
+fn zeroed_slices_that_are_too_big_fail() {
+    let bump = Bump::new();
+    assert!(bump.try_alloc_zeroed_slice::<u64>(usize::MAX).is_err());
+    assert!(bump.try_alloc_uninit_slice::<u64>(usize::MAX).is_err());
+}
+
+#[test]
+fn allocator_api_allocate_zeroed() {
+    let zeroing = Zeroing::default();
+    let bump = Bump::new_in(&zeroing);
+    bump.alloc(1_u8);
+
+    let layout = Layout::array::<u8>(64).unwrap();
+    let p = (&bump).allocate_zeroed(layout).unwrap();
+    assert!(unsafe { p.as_ref() }.iter().all(|&b| b == 0));
+    assert_eq!(zeroing.zeroed.get(), 0);
+
+    let layout = Layout::array::<u8>(100_000).unwrap();
+    let p = (&bump).allocate_zeroed(layout).unwrap();
+    assert!(unsafe { p.as_ref() }.iter().all(|&b| b == 0));
+    assert_eq!(zeroing.zeroed.get(), 1);
+}
+
+#[test]
+fn uninit_slices_can_be_initialized() {
+    let bump = Bump::new();
+    let x = bump.alloc_uninit::<u64>().write(42);
+    assert_eq!(*x, 42);
+
+    let xs = bump.alloc_uninit_slice::<String>(3);
+    assert_eq!(xs.len(), 3);
+    for (i, x) in xs.iter_mut().enumerate() {
+        x.write(i.to_string());
+    }
+    let xs = unsafe { &mut *(xs as *mut [MaybeUninit<String>] as *mut [String]) };
+    assert_eq!(xs, ["0", "1", "2"]);
+    unsafe { std::ptr::drop_in_place(xs) };
+}
+
+#[test]
+fn alloc_init_constructs_in_place() {
+    struct Big {
+        id: u32,
+        data: [u64; 1 << 16],
+    }
+
+    let bump = Bump::new();
+    let mut slot_ptr = std::ptr::null_mut();
+    let big = bump.alloc_init(|slot: &mut MaybeUninit<Big>| unsafe {
+        slot_ptr = slot.as_mut_ptr();
+        let p = slot.as_mut_ptr();
+        addr_of_mut!((*p).id).write(7);
+        for i in 0..1 << 16 {
+            addr_of_mut!((*p).data[i]).write(i as u64);
+        }
+        slot.assume_init_mut()
+    });
+    assert_eq!(big as *mut Big, slot_ptr);
+    assert_eq!(big.id, 7);
+    assert_eq!(big.data[1000], 1000);
+
+    let x = bump.try_alloc_init(|slot| slot.write(5_u8)).unwrap();
+    assert_eq!(*x, 5);
+}
@@ -0,0 +1,121 @@
+use bumpalo::{AllocErrKind, AllocationBudget, Bump, RetainPolicy};
+
+fn budgeted(budget: &AllocationBudget) -> Bump {
+    let mut bump = Bump::new();
+    bump.set_allocation_budget(Some(budget.clone()));
+    bump
+}
+
+#[test]
+fn arenas_share_one_budget() {
+    let budget = AllocationBudget::new(40 * 1024);
+    let a = budgeted(&budget);
+    let b = budgeted(&budget);
+
+    a.alloc([0_u8; 10_000]);
+    b.alloc([0_u8; 10_000]);
+    assert_eq!(
+        budget.used(),
+        a.allocated_bytes_including_metadata() + b.allocated_bytes_including_metadata()
+    );
+    assert!(budget.used() <= budget.limit());
+
+    let err = a.try_alloc([0_u8; 16 * 1024]).unwrap_err();
+    assert_eq!(err.kind(), AllocErrKind::AllocationBudget);
+
+    drop(b);
+    assert_eq!(budget.used(), a.allocated_bytes_including_metadata());
+    assert!(a.try_alloc([0_u8; 16 * 1024]).is_ok());
+
+    drop(a);
+    assert_eq!(budget.used(), 0);
+}
+
+#[test]
+fn smaller_chunks_are_tried_within_the_budget() {
+    let budget = AllocationBudget::new(8 * 1024);
+    let bump = budgeted(&budget);
+    for i in 0..1000_u32 {
+        bump.alloc(i);
+    }
+    // The next chunk would be twice as big as the previous one, which doesn't
+    // fit, so a smaller one is allocated instead.
+    bump.alloc([0_u8; 1024]);
+    assert!(budget.used() <= budget.limit());
+}
+
+#[test]
+fn chunks_return_to_the_budget_when_freed() {
+    let budget = AllocationBudget::new(1 << 20);
+    let mut bump = budgeted(&budget);
+    let fill = |bump: &Bump| {
+        for i in 0..10_000_u64 {
+            bump.alloc(i);
+        }
+    };
+
+    fill(&bump);
+    let used = budget.used();
+    assert_eq!(used, bump.allocated_bytes_including_metadata());
+    bump.reset();
+    assert!(0 < budget.used() && budget.used() < used);
+
+    fill(&bump);
+    bump.reset_with(RetainPolicy::UpTo(0));
+    assert_eq!(budget.used(), 0);
+
+    let checkpoint = bump.checkpoint();
+    fill(&bump);
+    unsafe { bump.reset_to(checkpoint) };
+    assert_eq!(budget.used(), 0);
+
+    fill(&bump);
+    let used = budget.used();
+    bump.reset_with(RetainPolicy::Merge);
+    assert!(0 < budget.used() && budget.used() < used);
+    assert_eq!(bump.iter_allocated_chunks().count(), 1);
+
+    drop(bump);
+    assert_eq!(budget.used(), 0);
+}
+
+#[test]
+fn existing_chunks_move_between_budgets() {
+    let first = AllocationBudget::new(1 << 20);
+    let second = AllocationBudget::new(16);
+    let mut bump = Bump::builder()
+        .capacity(1000)
+        .allocation_budget(Some(first.clone()))
+        .build();
+    assert_eq!(first.used(), bump.allocated_bytes_including_metadata());
+
+    // The chunk is drawn from the new budget even though it doesn't fit.
+    bump.set_allocation_budget(Some(second.clone()));
+    assert_eq!(first.used(), 0);
+    assert_eq!(second.used(), bump.allocated_bytes_including_metadata());
+    assert_eq!(second.remaining(), 0);
+    assert_eq!(
+        bump.try_alloc([0_u8; 2000]).unwrap_err().kind(),
+        AllocErrKind::AllocationBudget
+    );
+
+    drop(bump);
+    assert_eq!(second.used(), 0);
+}
+
+#[test]
+fn arenas_on_different_threads_share_a_budget() {
+    let budget = AllocationBudget::new(1 << 20);
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            let budget = budget.clone();
+            s.spawn(move || {
+                let bump = budgeted(&budget);
+                for i in 0..10_000_u32 {
+                    bump.alloc(i);
+                }
+            });
+        }
+    });
+    assert_eq!(budget.used(), 0);
+}
@@ -89,3 +89,65 @@ fn small_allocation_limit() {
     bump.set_allocation_limit(Some(64));
     assert!(bump.try_alloc([0; 1]).is_ok());
 }
+
+#[test]
+fn limit_errors_are_told_apart_from_allocator_errors() {
+    use bumpalo::{AllocErrKind, NoAlloc};
+    use std::alloc::Layout;
+
+    let bump = Bump::new();
+    bump.set_allocation_limit(Some(100));
+    let err = bump.try_alloc([0_u8; 200]).unwrap_err();
+    assert_eq!(err.kind(), AllocErrKind::AllocationLimit);
+    assert_eq!(err.layout(), Some(Layout::new::<[u8; 200]>()));
+    assert_eq!(err.headroom(), Some(100));
+
+    let bump = Bump::new_in(NoAlloc);
+    let err = bump.try_alloc(1_u32).unwrap_err();
+    assert_eq!(err.kind(), AllocErrKind::Allocator);
+    assert_eq!(err.layout(), Some(Layout::new::<u32>()));
+    assert_eq!(err.headroom(), None);
+
+    // The backing allocator running out of memory wins over bigger chunks
+    // being over the limit.
+    let bump = Bump::new_in(NoAlloc);
+    bump.set_allocation_limit(Some(1 << 20));
+    assert_eq!(
+        bump.try_alloc(1_u8).unwrap_err().kind(),
+        AllocErrKind::Allocator
+    );
+}
+
+#[test]
+fn headroom_accounts_for_existing_chunks() {
+    use bumpalo::AllocErrKind;
+
+    let bump = Bump::new();
+    bump.alloc(0_u8);
+    let allocated = bump.allocated_bytes();
+    bump.set_allocation_limit(Some(allocated + 100));
+
+    let err = bump
+        .try_alloc_layout(std::alloc::Layout::new::<[u64; 1024]>())
+        .unwrap_err();
+    assert_eq!(err.kind(), AllocErrKind::AllocationLimit);
+    assert_eq!(err.headroom(), Some(100));
+}
+
+#[cfg(feature = "collections")]
+#[test]
+fn limit_errors_reach_collections() {
+    use bumpalo::collections::{CollectionAllocErr, Vec};
+    use bumpalo::AllocErrKind;
+
+    let bump = Bump::new();
+    bump.set_allocation_limit(Some(0));
+    let mut v = Vec::<u64>::new_in(&bump);
+    match v.try_reserve(1000) {
+        Err(CollectionAllocErr::AllocErr(e)) => {
+            assert_eq!(e.kind(), AllocErrKind::AllocationLimit);
+            assert_eq!(e.headroom(), Some(0));
+        }
+        other => panic!("expected an allocation error, got {:?}", other),
+    }
+}
@@ -0,0 +1,82 @@
+use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+use bumpalo::Bump;
+use std::cell::Cell;
+use std::ptr::NonNull;
+
+/// An allocator that counts its live allocations and can be told to fail.
+#[derive(Default)]
+struct Counting {
+    live: Cell<usize>,
+    fail: Cell<bool>,
+}
+
+unsafe impl Allocator for Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if self.fail.get() {
+            return Err(AllocError);
+        }
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn chunks_come_from_backing_allocator() {
+    let counting = Counting::default();
+    {
+        let bump = Bump::new_in(&counting);
+        assert_eq!(counting.live.get(), 0);
+
+        bump.alloc(1_u64);
+        assert_eq!(counting.live.get(), 1);
+
+        // Force a couple more chunks.
+        bump.alloc_slice_fill_copy(10_000, 0_u8);
+        bump.alloc_slice_fill_copy(100_000, 0_u8);
+        assert_eq!(counting.live.get(), 3);
+    }
+    assert_eq!(counting.live.get(), 0);
+}
+
+#[test]
+fn reset_returns_chunks_to_backing_allocator() {
+    let counting = Counting::default();
+    let mut bump = Bump::with_capacity_in(16, &counting);
+    assert_eq!(counting.live.get(), 1);
+
+    bump.alloc_slice_fill_copy(10_000, 0_u8);
+    bump.alloc_slice_fill_copy(100_000, 0_u8);
+    assert_eq!(counting.live.get(), 3);
+
+    bump.reset();
+    assert_eq!(counting.live.get(), 1);
+
+    drop(bump);
+    assert_eq!(counting.live.get(), 0);
+}
+
+#[test]
+fn backing_allocator_failures() {
+    let counting = Counting::default();
+    counting.fail.set(true);
+
+    assert!(Bump::try_with_capacity_in(1, &counting).is_err());
+
+    let bump = Bump::try_new_in(&counting).unwrap();
+    assert!(bump.try_alloc(1_u8).is_err());
+
+    counting.fail.set(false);
+    assert_eq!(bump.try_alloc(1_u8), Ok(&mut 1));
+}
+
+#[test]
+fn allocator_accessor() {
+    let bump = Bump::new_in(Counting::default());
+    bump.alloc(0_u32);
+    assert_eq!(bump.allocator().live.get(), 1);
+}
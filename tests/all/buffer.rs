@@ -0,0 +1,182 @@
+use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+use bumpalo::{Buffer, Bump, NoAlloc};
+use std::cell::Cell;
+use std::mem::MaybeUninit;
+use std::ptr::NonNull;
+
+/// An allocator that counts its live allocations.
+#[derive(Default)]
+struct Counting {
+    live: Cell<usize>,
+}
+
+unsafe impl Allocator for Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn allocations_come_from_the_buffer() {
+    let mut buffer = [MaybeUninit::uninit(); 1024];
+    let range = buffer.as_ptr_range();
+    let mut bump = Bump::from_buffer(&mut buffer);
+
+    let mut ptrs = vec![];
+    while let Ok(x) = bump.try_alloc(7_u64) {
+        ptrs.push(x as *mut u64 as usize);
+    }
+    assert!(!ptrs.is_empty());
+    for &p in &ptrs {
+        assert!(range.start as usize <= p && p + 8 <= range.end as usize);
+        assert_eq!(p % 8, 0);
+    }
+
+    assert!(bump.allocated_bytes() > 0);
+    assert!(bump.allocated_bytes() <= 1024);
+    assert!(bump.try_alloc(0_u8).is_err());
+
+    let chunks: Vec<_> = bump.iter_allocated_chunks().collect();
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].len(), ptrs.len() * 8);
+
+    // Resetting makes the whole buffer available again.
+    bump.reset();
+    let again = bump.alloc_slice_fill_copy(ptrs.len(), 0_u64);
+    assert_eq!(again.len(), ptrs.len());
+}
+
+#[test]
+fn unaligned_buffer() {
+    let mut buffer = [MaybeUninit::uninit(); 1024];
+    let bump = Bump::from_buffer(&mut buffer[1..]);
+    let x = bump.alloc(1_u128) as *mut u128;
+    assert_eq!(x as usize % std::mem::align_of::<u128>(), 0);
+}
+
+#[test]
+fn buffer_too_small() {
+    let mut buffer = [MaybeUninit::uninit(); 8];
+    let bump = Bump::from_buffer(&mut buffer);
+    assert_eq!(bump.allocated_bytes(), 0);
+    assert!(bump.try_alloc(0_u8).is_err());
+
+    let mut buffer: [MaybeUninit<u8>; 0] = [];
+    let bump = Bump::from_buffer(&mut buffer);
+    assert!(bump.try_alloc(0_u8).is_err());
+}
+
+#[test]
+fn fallback_allocator() {
+    let counting = Counting::default();
+    let mut buffer = [MaybeUninit::uninit(); 1024];
+    let range = buffer.as_ptr_range();
+    {
+        let mut bump = Bump::from_buffer_in(&mut buffer, &counting);
+        let x = bump.alloc(1_u32) as *mut u32 as *const u8;
+        assert!(range.start as *const u8 <= x && x < range.end as *const u8);
+        assert_eq!(counting.live.get(), 0);
+
+        // Too big for the buffer, so it goes to the fallback allocator.
+        bump.alloc([0_u8; 2048]);
+        assert_eq!(counting.live.get(), 1);
+        assert_eq!(bump.iter_allocated_chunks().count(), 2);
+
+        // Rolling back to before the fallback chunk gives it back, but keeps
+        // the buffer.
+        let checkpoint = bump.checkpoint();
+        bump.alloc([0_u8; 10_000]);
+        assert_eq!(counting.live.get(), 2);
+        unsafe { bump.reset_to(checkpoint) };
+        assert_eq!(counting.live.get(), 1);
+
+        // Resetting keeps only the newest chunk, which is the fallback one.
+        bump.reset();
+        assert_eq!(counting.live.get(), 1);
+        assert_eq!(bump.iter_allocated_chunks().count(), 1);
+    }
+    assert_eq!(counting.live.get(), 0);
+}
+
+#[test]
+fn reset_keeps_the_buffer_if_it_is_the_current_chunk() {
+    let counting = Counting::default();
+    let mut buffer = [MaybeUninit::uninit(); 1024];
+    let range = buffer.as_ptr_range();
+    let mut bump = Bump::from_buffer_in(&mut buffer, &counting);
+    bump.alloc(1_u32);
+    bump.reset();
+
+    let x = bump.alloc(2_u32) as *mut u32 as *const u8;
+    assert!(range.start as *const u8 <= x && x < range.end as *const u8);
+    assert_eq!(counting.live.get(), 0);
+}
+
+#[test]
+fn build_with_buffer() {
+    let mut buffer = [MaybeUninit::uninit(); 1024];
+    let bump = Bump::builder()
+        .allocator(NoAlloc)
+        .min_align::<8>()
+        .upward()
+        .build_with_buffer(&mut buffer);
+
+    let a = bump.alloc(1_u8) as *mut u8;
+    let b = bump.alloc(2_u8) as *mut u8;
+    assert_eq!(b, a.wrapping_add(8));
+    assert!(bump.try_alloc([0_u8; 2048]).is_err());
+}
+
+#[test]
+fn build_with_buffer_respects_allocation_limit() {
+    let mut buffer = [MaybeUninit::uninit(); 1024];
+    let range = buffer.as_ptr_range();
+    let bump = Bump::builder()
+        .allocation_limit(Some(4096))
+        .build_with_buffer(&mut buffer);
+
+    let small = bump.alloc(1_u8) as *mut u8 as *const u8;
+    assert!(range.start as *const u8 <= small && small < range.end as *const u8);
+    assert!(bump.try_alloc([0_u8; 1024]).is_ok());
+    assert!(bump.try_alloc([0_u8; 8192]).is_err());
+}
+
+#[test]
+fn moving_the_arena_keeps_its_buffer_chunk_in_place() {
+    let mut buffer = [MaybeUninit::uninit(); 1024];
+    let range = buffer.as_ptr_range();
+    let in_buffer = |p: *const u8| range.contains(&p.cast());
+    let bump = Bump::from_buffer_in(&mut buffer, Global);
+    let a = bump.alloc(b'a') as *const u8;
+    let checkpoint = bump.checkpoint();
+    bump.alloc_slice_fill_copy(2000, b'x');
+
+    let mut moved = Box::new(bump);
+    let b = moved.alloc(b'b') as *const u8;
+    assert!(in_buffer(a));
+    assert!(!in_buffer(b));
+
+    // Checkpoints from before the move are still valid.
+    unsafe { moved.reset_to(checkpoint) };
+    let c = moved.alloc(b'c') as *const u8;
+    assert!(in_buffer(c));
+    assert!(c < a);
+    assert_eq!(moved.iter_allocated_chunks().count(), 1);
+}
+
+// Checks at compile time that arenas are covariant in their backing
+// allocator, so that an arena over a longer-lived buffer can be used where
+// one over a shorter-lived buffer is expected.
+#[allow(dead_code)]
+fn bump_is_covariant_in_its_allocator<'short, 'long: 'short>(
+    bump: Bump<Buffer<'long>>,
+    bump_ref: &'short Bump<Buffer<'long>>,
+) -> (Bump<Buffer<'short>>, &'short Bump<Buffer<'short>>) {
+    (bump, bump_ref)
+}
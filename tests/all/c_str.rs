@@ -0,0 +1,54 @@
+use bumpalo::Bump;
+use std::ffi::CStr;
+
+#[test]
+fn alloc_c_str_copies() {
+    let bump = Bump::new();
+    let src = CStr::from_bytes_with_nul(b"hello\0").unwrap();
+    let copy = bump.alloc_c_str(src);
+    assert_eq!(copy, src);
+    assert_ne!(copy.as_ptr(), src.as_ptr());
+
+    let empty = bump.alloc_c_str(CStr::from_bytes_with_nul(b"\0").unwrap());
+    assert_eq!(empty.to_bytes_with_nul(), b"\0");
+}
+
+#[test]
+fn alloc_str_as_c_str_adds_a_nul_terminator() {
+    let bump = Bump::new();
+    for s in ["", "a", "hello, world", "üñíçødé"] {
+        let c_str = bump.alloc_str_as_c_str(s).unwrap();
+        assert_eq!(c_str.to_str(), Ok(s));
+        assert_eq!(c_str.to_bytes_with_nul().len(), s.len() + 1);
+    }
+}
+
+#[test]
+fn alloc_str_as_c_str_rejects_nul_bytes() {
+    let bump = Bump::new();
+    for (s, position) in [("\0", 0), ("abc\0", 3), ("a\0b\0", 1)] {
+        let err = bump.alloc_str_as_c_str(s).unwrap_err();
+        assert_eq!(err.nul_position(), position);
+    }
+    assert_eq!(
+        bump.alloc_str_as_c_str("ab\0").unwrap_err().to_string(),
+        "nul byte found in provided data at position: 2"
+    );
+}
+
+#[cfg(all(feature = "std", unix))]
+#[test]
+fn alloc_os_str_and_path_copy() {
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    let bump = Bump::new();
+    let name = bump.alloc_os_str(OsStr::new("name.txt"));
+    assert_eq!(name, "name.txt");
+
+    let src = Path::new("some/dir/name.txt");
+    let path = bump.alloc_path(src);
+    assert_eq!(path, src);
+    assert_eq!(path.file_name(), Some(OsStr::new("name.txt")));
+    assert_ne!(path.as_os_str().len(), 0);
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "collections")]
+use bumpalo::{collections::CString, Bump};
+use std::ffi::CStr;
+use std::num::NonZeroU8;
+
+#[test]
+fn nul_terminator_is_kept_while_growing() {
+    let b = Bump::new();
+    let mut s = CString::new_in(&b);
+    for i in 0..1000_u32 {
+        s.push_str(&i.to_string()).unwrap();
+        s.push(NonZeroU8::new(b',').unwrap());
+        assert_eq!(s.as_bytes_with_nul().last(), Some(&0));
+        assert_eq!(s.to_bytes().len(), s.len());
+    }
+    let expected: String = (0..1000).map(|i| format!("{},", i)).collect();
+    assert_eq!(s.to_str(), Ok(&expected[..]));
+}
+
+#[test]
+fn nul_bytes_are_rejected() {
+    let b = Bump::new();
+    assert_eq!(
+        CString::from_bytes_in(b"ab\0", &b)
+            .unwrap_err()
+            .nul_position(),
+        2
+    );
+
+    let mut s = CString::from_bytes_in(b"abc", &b).unwrap();
+    assert_eq!(s.push_bytes(b"d\0e").unwrap_err().nul_position(), 1);
+    assert!(s.push_str("\0").is_err());
+    assert_eq!(s.as_bytes_with_nul(), b"abc\0");
+}
+
+#[test]
+fn shrinking() {
+    let b = Bump::new();
+    let mut s = CString::from_bytes_in(b"abcdef", &b).unwrap();
+    assert_eq!(s.pop(), NonZeroU8::new(b'f'));
+    s.truncate(10);
+    assert_eq!(s.as_bytes_with_nul(), b"abcde\0");
+    s.truncate(2);
+    assert_eq!(s.as_bytes_with_nul(), b"ab\0");
+    s.clear();
+    assert!(s.is_empty());
+    assert_eq!(s.pop(), None);
+    assert_eq!(s.as_bytes_with_nul(), b"\0");
+}
+
+#[test]
+fn conversions() {
+    let b = Bump::new();
+    let c_str = CStr::from_bytes_with_nul(b"hello\0").unwrap();
+    let s = CString::from_c_str_in(c_str, &b);
+    assert_eq!(s, c_str);
+    assert_eq!(s.as_ref(), c_str);
+    assert_eq!(format!("{:?}", s), format!("{:?}", c_str));
+    assert_eq!(s.clone().into_bytes(), b"hello");
+
+    let mut s = s;
+    s.reserve(100);
+    assert!(s.capacity() >= 105);
+    s.try_reserve(10).unwrap();
+    let c_str: &CStr = s.into_bump_c_str();
+    assert_eq!(c_str.to_bytes_with_nul(), b"hello\0");
+}
@@ -0,0 +1,109 @@
+use bumpalo::Bump;
+
+#[test]
+fn reset_to_within_same_chunk() {
+    let bump = Bump::with_capacity(1024);
+    let x = bump.alloc(1_u64);
+
+    let checkpoint = bump.checkpoint();
+    let capacity = bump.chunk_capacity();
+    bump.alloc([0_u8; 100]);
+    assert!(bump.chunk_capacity() < capacity);
+
+    unsafe {
+        bump.reset_to(checkpoint);
+    }
+    assert_eq!(bump.chunk_capacity(), capacity);
+    assert_eq!(*x, 1);
+}
+
+#[test]
+fn reset_to_frees_newer_chunks() {
+    let mut bump = Bump::new();
+    let x = bump.alloc(42_u32) as *const u32;
+
+    let checkpoint = bump.checkpoint();
+    let allocated_bytes = bump.allocated_bytes();
+    let chunk_capacity = bump.chunk_capacity();
+
+    for i in 0..10_000 {
+        bump.alloc(i);
+    }
+    assert!(bump.allocated_bytes() > allocated_bytes);
+
+    unsafe {
+        bump.reset_to(checkpoint);
+    }
+    assert_eq!(bump.allocated_bytes(), allocated_bytes);
+    assert_eq!(bump.chunk_capacity(), chunk_capacity);
+    assert_eq!(bump.iter_allocated_chunks().count(), 1);
+    assert_eq!(unsafe { *x }, 42);
+}
+
+#[test]
+fn reset_to_empty_arena() {
+    let mut bump = Bump::new();
+    let checkpoint = bump.checkpoint();
+
+    bump.alloc_slice_fill_copy(10_000, 1_u8);
+    assert!(bump.allocated_bytes() > 0);
+
+    unsafe {
+        bump.reset_to(checkpoint);
+    }
+    assert_eq!(bump.allocated_bytes(), 0);
+    assert_eq!(bump.iter_allocated_chunks().count(), 0);
+
+    // The arena is still usable afterwards.
+    assert_eq!(*bump.alloc(5), 5);
+}
+
+#[test]
+fn nested_checkpoints() {
+    let bump = Bump::new();
+    let a = bump.alloc(1);
+    let outer = bump.checkpoint();
+    let b = bump.alloc(2) as *const i32;
+    let inner = bump.checkpoint();
+    bump.alloc_slice_fill_copy(5_000, 3_u8);
+
+    unsafe {
+        bump.reset_to(inner);
+        assert_eq!(*b, 2);
+        bump.reset_to(outer);
+    }
+    assert_eq!(*a, 1);
+    assert_eq!(bump.checkpoint(), outer);
+}
+
+#[test]
+fn scope_rolls_back() {
+    let mut bump = Bump::new();
+    bump.alloc(0_u8);
+    let allocated_bytes = bump.allocated_bytes();
+    let chunk_capacity = bump.chunk_capacity();
+
+    let len = bump.scope(|bump| {
+        let v = bump.alloc_slice_fill_with(10_000, |i| i as u32);
+        v.len()
+    });
+    assert_eq!(len, 10_000);
+    assert_eq!(bump.allocated_bytes(), allocated_bytes);
+    assert_eq!(bump.chunk_capacity(), chunk_capacity);
+}
+
+#[test]
+fn scope_rolls_back_on_panic() {
+    let mut bump = Bump::new();
+    let checkpoint = bump.checkpoint();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        bump.scope(|bump| {
+            bump.alloc_slice_fill_copy(10_000, 0_u8);
+            panic!("oops");
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(bump.checkpoint(), checkpoint);
+    assert_eq!(bump.allocated_bytes(), 0);
+}
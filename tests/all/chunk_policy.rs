@@ -0,0 +1,132 @@
+use bumpalo::Bump;
+
+/// Allocate `n` values of `size` bytes each and return the sizes of the chunks
+/// that were allocated along the way.
+fn chunk_sizes(bump: &Bump, n: usize, size: usize) -> Vec<usize> {
+    let mut sizes = vec![];
+    let mut allocated = bump.allocated_bytes();
+    for _ in 0..n {
+        bump.alloc_slice_fill_copy(size, 0_u8);
+        let now = bump.allocated_bytes();
+        if now != allocated {
+            sizes.push(now - allocated);
+            allocated = now;
+        }
+    }
+    sizes
+}
+
+#[test]
+fn default_policy_doubles() {
+    let bump = Bump::builder().build();
+    let sizes = chunk_sizes(&bump, 1000, 100);
+    assert!(sizes.len() > 3);
+    for w in sizes.windows(2) {
+        assert!(w[1] >= 2 * w[0] - 64, "{:?}", sizes);
+    }
+}
+
+#[test]
+fn growth_factor_one_keeps_chunk_sizes_constant() {
+    let bump = Bump::builder().capacity(4000).growth_factor(1).build();
+    let first = bump.allocated_bytes();
+    let sizes = chunk_sizes(&bump, 1000, 100);
+    assert!(sizes.len() > 3);
+    assert!(sizes.iter().all(|&s| s == first), "{:?}", sizes);
+}
+
+#[test]
+fn growth_factor_four() {
+    let bump = Bump::builder().growth_factor(4).build();
+    let sizes = chunk_sizes(&bump, 2000, 100);
+    assert!(sizes.len() > 2);
+    for w in sizes.windows(2) {
+        assert!(w[1] >= 4 * w[0] - 64, "{:?}", sizes);
+    }
+}
+
+#[test]
+#[should_panic]
+fn growth_factor_zero_panics() {
+    let _ = Bump::builder().growth_factor(0);
+}
+
+#[test]
+fn max_chunk_size_caps_chunks() {
+    const MAX: usize = 8192;
+    let bump = Bump::builder().max_chunk_size(Some(MAX)).build();
+    let sizes = chunk_sizes(&bump, 2000, 100);
+    assert!(sizes.len() > 10);
+    assert!(sizes.iter().all(|&s| s <= MAX), "{:?}", sizes);
+    assert_eq!(*sizes.last().unwrap(), MAX);
+}
+
+#[test]
+fn max_chunk_size_dedicated_chunk_for_big_requests() {
+    const MAX: usize = 8192;
+    let bump = Bump::builder().max_chunk_size(Some(MAX)).build();
+    bump.alloc(0_u8);
+
+    let before = bump.allocated_bytes();
+    bump.alloc_slice_fill_copy(3 * MAX, 0_u8);
+    let big_chunk = bump.allocated_bytes() - before;
+    assert!(big_chunk >= 3 * MAX);
+    assert!(big_chunk < 3 * MAX + 64);
+
+    // Subsequent chunks are capped again.
+    let sizes = chunk_sizes(&bump, 200, 100);
+    assert!(sizes.iter().all(|&s| s <= MAX), "{:?}", sizes);
+}
+
+#[test]
+fn fixed_chunk_size() {
+    const SIZE: usize = 1000;
+    let bump = Bump::builder().fixed_chunk_size(Some(SIZE)).build();
+    let sizes = chunk_sizes(&bump, 100, 100);
+    assert!(sizes.len() > 5);
+    // Rounded up to the chunk alignment.
+    assert!(sizes.iter().all(|&s| s == 1008), "{:?}", sizes);
+
+    // Requests that don't fit get their own chunk.
+    let before = bump.allocated_bytes();
+    bump.alloc_slice_fill_copy(5000, 0_u8);
+    assert_eq!(bump.allocated_bytes() - before, 5008);
+
+    // And then we go back to fixed-size chunks.
+    let sizes = chunk_sizes(&bump, 20, 100);
+    assert!(sizes.iter().all(|&s| s == 1008), "{:?}", sizes);
+}
+
+#[test]
+fn fixed_chunk_size_with_capacity() {
+    let bump = Bump::builder()
+        .fixed_chunk_size(Some(256))
+        .capacity(100)
+        .build();
+    assert_eq!(bump.allocated_bytes(), 256);
+}
+
+#[test]
+fn policy_respects_allocation_limit() {
+    let bump = Bump::builder()
+        .fixed_chunk_size(Some(1024))
+        .allocation_limit(Some(4096))
+        .build();
+    assert_eq!(bump.allocation_limit(), Some(4096));
+
+    for _ in 0..4 {
+        assert!(bump.try_alloc([0_u8; 1000]).is_ok());
+    }
+    assert!(bump.try_alloc([0_u8; 1000]).is_err());
+    assert_eq!(bump.allocated_bytes(), 4096);
+}
+
+#[test]
+fn builder_with_allocator() {
+    let bump = Bump::builder()
+        .allocator(allocator_api2::alloc::Global)
+        .capacity(64)
+        .build();
+    assert!(bump.chunk_capacity() >= 64);
+    assert!(Bump::builder().capacity(64).try_build().is_ok());
+}
@@ -0,0 +1,141 @@
+use allocator_api2::alloc::Allocator;
+use bumpalo::{Bump, Global};
+use std::alloc::Layout;
+use std::slice;
+
+fn bytes(ptr: *const u8, len: usize) -> Vec<u8> {
+    unsafe { slice::from_raw_parts(ptr, len).to_vec() }
+}
+
+#[test]
+fn fresh_and_freed_memory_is_filled() {
+    let mut bump = Bump::new();
+    let layout = Layout::new::<[u8; 8]>();
+    let p = bump.alloc_layout(layout).as_ptr();
+    assert_eq!(bytes(p, 8), [0xCD; 8]);
+
+    let q = (&bump).allocate(layout).unwrap().cast::<u8>();
+    unsafe { (&bump).deallocate(q, layout) };
+    assert_eq!(bytes(q.as_ptr(), 8), [0xDD; 8]);
+
+    bump.reset();
+    assert_eq!(bytes(p, 8), [0xDD; 8]);
+}
+
+#[test]
+fn overruns_are_reported_with_their_allocation() {
+    let bump = Bump::builder().red_zones(true).build();
+    bump.alloc(1_u64);
+    let p = bump.alloc_layout(Layout::new::<[u32; 3]>()).as_ptr();
+    bump.alloc(2_u64);
+    assert_eq!(bump.check_integrity(), Ok(()));
+
+    unsafe { p.add(13).write(0) };
+    let err = bump.check_integrity().unwrap_err();
+    assert_eq!(err.allocation(), p.cast_const());
+    assert_eq!(err.layout(), Layout::new::<[u32; 3]>());
+    assert_eq!(err.corrupted(), p.wrapping_add(13).cast_const());
+    assert!(err
+        .to_string()
+        .contains("at offset 13 of the 12-byte allocation"));
+
+    unsafe { p.add(13).write(0xFD) };
+    assert_eq!(bump.check_integrity(), Ok(()));
+}
+
+#[test]
+fn upward_overruns_are_reported_with_their_allocation() {
+    let bump = Bump::builder().upward().red_zones(true).build();
+    let p = bump.alloc_layout(Layout::new::<[u8; 5]>()).as_ptr();
+    bump.alloc(1_u32);
+    assert_eq!(bump.check_integrity(), Ok(()));
+
+    unsafe { p.add(5).write(0) };
+    let err = bump.check_integrity().unwrap_err();
+    assert_eq!(err.allocation(), p.cast_const());
+    assert_eq!(err.corrupted(), p.wrapping_add(5).cast_const());
+
+    unsafe { p.add(5).write(0xFD) };
+}
+
+#[test]
+#[should_panic(expected = "red zone overwritten")]
+fn reset_panics_on_overruns() {
+    let mut bump = Bump::builder().red_zones(true).build();
+    let p = bump.alloc_layout(Layout::new::<u8>()).as_ptr();
+    unsafe { p.add(1).write(0) };
+    bump.reset();
+}
+
+#[test]
+#[should_panic(expected = "red zone overwritten")]
+fn drop_panics_on_overruns() {
+    let bump = Bump::builder().red_zones(true).build();
+    let p = bump.alloc_layout(Layout::new::<u8>()).as_ptr();
+    unsafe { p.add(1).write(0) };
+    drop(bump);
+}
+
+fn check_reused_memory<const UP: bool>(mut bump: Bump<Global, 1, UP>) {
+    let _ = bump.alloc_try_with(|| Err::<[u8; 32], _>(()));
+    bump.scope(|bump| {
+        bump.alloc([0xAB_u8; 64]);
+    });
+    let layout = Layout::new::<[u8; 16]>();
+    let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+    unsafe { (&bump).deallocate(p, layout) };
+
+    // All of the above left red zones in memory that this overwrites.
+    bump.alloc([0xAB_u8; 256]);
+    assert_eq!(bump.check_integrity(), Ok(()));
+
+    // Growing and shrinking the last allocation in place keeps its red zone
+    // next to it.
+    let layout = Layout::new::<[u8; 8]>();
+    let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+    let grown = Layout::new::<[u8; 64]>();
+    let p = unsafe { (&bump).grow(p, layout, grown).unwrap().cast::<u8>() };
+    unsafe { p.as_ptr().write_bytes(0xAB, 64) };
+    let p = unsafe { (&bump).shrink(p, grown, layout).unwrap().cast::<u8>() };
+    unsafe { p.as_ptr().write_bytes(0xAB, 8) };
+    assert_eq!(bump.check_integrity(), Ok(()));
+}
+
+#[test]
+fn reused_memory_is_not_mistaken_for_overruns() {
+    check_reused_memory(Bump::builder().red_zones(true).build());
+    check_reused_memory(Bump::builder().upward().red_zones(true).build());
+}
+
+#[test]
+fn red_zones_survive_moving_an_arena_over_a_buffer() {
+    let mut buffer = [std::mem::MaybeUninit::uninit(); 1024];
+    let bump = Bump::builder()
+        .allocator(Global)
+        .red_zones(true)
+        .build_with_buffer(&mut buffer);
+    bump.alloc(1_u32);
+    let bump = Box::new(bump);
+    let p = bump.alloc_layout(Layout::new::<u8>()).as_ptr();
+    assert_eq!(bump.check_integrity(), Ok(()));
+
+    unsafe { p.add(1).write(0) };
+    assert_eq!(
+        bump.check_integrity().unwrap_err().allocation(),
+        p.cast_const()
+    );
+    unsafe { p.add(1).write(0xFD) };
+}
+
+#[test]
+fn red_zones_are_opt_in() {
+    let bump = Bump::new();
+    let a = bump.alloc_layout(Layout::new::<[u8; 5]>()).as_ptr();
+    assert_eq!(bytes(a, 5), [0xCD; 5]);
+    let b = bump.alloc_layout(Layout::new::<[u8; 3]>()).as_ptr();
+    assert_eq!(b, a.wrapping_sub(3));
+
+    // Nothing is checked, so writing past the end goes unnoticed.
+    unsafe { b.add(3).write(0) };
+    assert_eq!(bump.check_integrity(), Ok(()));
+}
@@ -0,0 +1,106 @@
+use bumpalo::DropBump;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+/// Records its name in a shared log when dropped.
+struct Noisy<'a>(&'a str, &'a RefCell<Vec<String>>);
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0.to_string());
+    }
+}
+
+#[test]
+fn drops_in_reverse_order() {
+    let log = RefCell::new(vec![]);
+    {
+        let bump = DropBump::new();
+        bump.alloc(Noisy("a", &log));
+        bump.alloc_with(|| Noisy("b", &log));
+        bump.alloc_slice_fill_with(2, |i| Noisy(["c", "d"][i], &log));
+        bump.alloc(Noisy("e", &log));
+        assert!(log.borrow().is_empty());
+    }
+    assert_eq!(*log.borrow(), ["e", "c", "d", "b", "a"]);
+}
+
+#[test]
+fn reset_drops_values() {
+    let shared = Rc::new(());
+    let mut bump = DropBump::new();
+    for _ in 0..100 {
+        bump.alloc(Rc::clone(&shared));
+    }
+    bump.alloc_slice_clone(&[Rc::clone(&shared), Rc::clone(&shared)]);
+    assert_eq!(Rc::strong_count(&shared), 103);
+
+    bump.reset();
+    assert_eq!(Rc::strong_count(&shared), 1);
+
+    // The arena is usable again, and values allocated after the reset are
+    // dropped with it.
+    bump.alloc(Rc::clone(&shared));
+    assert_eq!(Rc::strong_count(&shared), 2);
+    drop(bump);
+    assert_eq!(Rc::strong_count(&shared), 1);
+}
+
+#[test]
+fn values_that_need_no_drop_are_not_tracked() {
+    let bump = DropBump::new();
+    let a = bump.alloc(1_u64) as *mut u64 as usize;
+    let b = bump.alloc(2_u64) as *mut u64 as usize;
+    assert_eq!(a, b + 8);
+
+    let c = bump.alloc_slice_fill_with(4, |i| i as u32).as_ptr() as usize;
+    assert_eq!(c + 16, b);
+
+    // Untracked values allocated through the underlying `Bump` are fine too.
+    let d = bump.alloc_str("hello").as_ptr() as usize;
+    assert_eq!(d + 5, c);
+}
+
+#[test]
+fn zero_sized_values_are_dropped() {
+    let count = Rc::new(RefCell::new(0));
+    struct Zst(Rc<RefCell<u32>>);
+    impl Drop for Zst {
+        fn drop(&mut self) {
+            *self.0.borrow_mut() += 1;
+        }
+    }
+    {
+        let bump = DropBump::new();
+        bump.alloc_slice_fill_with(0, |_| Zst(Rc::clone(&count)));
+        bump.alloc(Zst(Rc::clone(&count)));
+        bump.alloc_slice_fill_with(3, |_| Zst(Rc::clone(&count)));
+    }
+    assert_eq!(*count.borrow(), 4);
+}
+
+#[test]
+fn panicking_destructor_does_not_drop_twice() {
+    let log = RefCell::new(vec![]);
+
+    struct Bomb;
+    impl Drop for Bomb {
+        fn drop(&mut self) {
+            panic!("boom");
+        }
+    }
+
+    let mut bump = DropBump::new();
+    bump.alloc(Noisy("leaked", &log));
+    bump.alloc(Bomb);
+    bump.alloc(Noisy("dropped", &log));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| bump.reset()));
+    assert!(result.is_err());
+    assert_eq!(*log.borrow(), ["dropped"]);
+
+    // Nothing is left to drop.
+    drop(bump);
+    assert_eq!(*log.borrow(), ["dropped"]);
+}
@@ -0,0 +1,67 @@
+use bumpalo::Herd;
+use std::thread;
+
+#[test]
+fn allocations_outlive_members_and_threads() {
+    let herd = Herd::new();
+    let per_thread: Vec<&[u32]> = thread::scope(|s| {
+        let handles: Vec<_> = (0..8_u32)
+            .map(|t| {
+                let herd = &herd;
+                s.spawn(move || {
+                    let member = herd.get();
+                    for i in 0..1000 {
+                        member.alloc(i);
+                    }
+                    &*member.alloc_slice_fill_with(100, |i| t * 1000 + i as u32)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    for (t, xs) in per_thread.iter().enumerate() {
+        assert!(xs
+            .iter()
+            .enumerate()
+            .all(|(i, x)| *x as usize == t * 1000 + i));
+    }
+}
+
+#[test]
+fn members_reuse_returned_arenas() {
+    let mut herd = Herd::new();
+    let first = {
+        let member = herd.get();
+        member.alloc(1_u64) as *mut u64
+    };
+    let second = herd.get().alloc(2_u64) as *mut u64;
+    assert_eq!(second as usize + 8, first as usize);
+
+    // Two members at once get two different arenas.
+    let a = herd.get();
+    let b = herd.get();
+    assert!(!std::ptr::eq(a.as_bump(), b.as_bump()));
+    drop((a, b));
+
+    let allocated_bytes = herd.allocated_bytes();
+    assert!(allocated_bytes > 0);
+}
+
+#[test]
+fn reset_returns_excess_chunks() {
+    let mut herd = Herd::new();
+    {
+        let member = herd.get();
+        for i in 0..10_000_u64 {
+            member.alloc(i);
+        }
+        member.alloc_str("hello");
+    }
+    let before = herd.allocated_bytes();
+    herd.reset();
+    assert!(herd.allocated_bytes() < before);
+
+    let member = herd.get();
+    assert_eq!(member.alloc_slice_copy(&[1, 2, 3]), &[1, 2, 3]);
+}
@@ -1,22 +1,55 @@
 #![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 
 mod alloc_fill;
+mod alloc_fmt;
+mod alloc_slice_from_iter;
 mod alloc_try_with;
+mod alloc_uninit;
 mod alloc_with;
 mod allocation_limit;
 mod allocator_api;
+mod backing_allocator;
 mod boxed;
+mod buffer;
+mod c_str;
+mod c_string;
 mod capacity;
+mod checkpoint;
+mod chunk_policy;
 mod collect_in;
+mod drop_bump;
+mod min_align;
+mod oom_handler;
 mod quickcheck;
 mod quickchecks;
+mod reset_with;
 mod string;
 mod tests;
 mod try_alloc_try_with;
 mod try_alloc_with;
+mod typed_bump;
+mod upward;
 mod vec;
 
+#[cfg(feature = "debug-checks")]
+mod debug_checks;
+
+#[cfg(feature = "sanitize")]
+mod sanitize;
+
 #[cfg(feature = "serde")]
 mod serde;
 
+#[cfg(feature = "stats")]
+mod stats;
+
+#[cfg(feature = "std")]
+mod allocation_budget;
+#[cfg(feature = "std")]
+mod herd;
+#[cfg(feature = "std")]
+mod sync_bump;
+#[cfg(all(feature = "std", target_os = "linux", not(miri)))]
+mod vm_bump;
+
 fn main() {}
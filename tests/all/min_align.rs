@@ -0,0 +1,117 @@
+use crate::quickcheck;
+use allocator_api2::alloc::{Allocator, Global, Layout};
+use bumpalo::Bump;
+
+/// Allocate each of the given `(size, align)` pairs from a fresh arena with
+/// the given `MIN_ALIGN` and check that the allocations are aligned to at
+/// least `MIN_ALIGN` and don't overlap.
+fn check_min_align<const MIN_ALIGN: usize>(layouts: &[(usize, usize)]) {
+    let bump = Bump::<Global, MIN_ALIGN>::with_min_align();
+    let mut regions = vec![];
+
+    for &(size, align) in layouts {
+        let align = 1 << (align % 6);
+        let size = size % 512;
+        let layout = Layout::from_size_align(size, align).unwrap();
+        let ptr = bump.alloc_layout(layout).as_ptr() as usize;
+
+        assert_eq!(ptr % align, 0);
+        assert_eq!(ptr % MIN_ALIGN, 0);
+        assert_eq!(bump.chunk_capacity() % MIN_ALIGN, 0);
+
+        regions.push((ptr, size));
+    }
+
+    regions.sort();
+    for w in regions.windows(2) {
+        assert!(
+            w[0].0 + w[0].1 <= w[1].0,
+            "overlapping allocations: {:?}",
+            w
+        );
+    }
+}
+
+quickcheck! {
+    fn min_align_1(layouts: Vec<(usize, usize)>) -> () {
+        check_min_align::<1>(&layouts);
+    }
+
+    fn min_align_2(layouts: Vec<(usize, usize)>) -> () {
+        check_min_align::<2>(&layouts);
+    }
+
+    fn min_align_4(layouts: Vec<(usize, usize)>) -> () {
+        check_min_align::<4>(&layouts);
+    }
+
+    fn min_align_8(layouts: Vec<(usize, usize)>) -> () {
+        check_min_align::<8>(&layouts);
+    }
+
+    fn min_align_16(layouts: Vec<(usize, usize)>) -> () {
+        check_min_align::<16>(&layouts);
+    }
+}
+
+#[test]
+fn min_align_rounds_sizes_up() {
+    let bump = Bump::<Global, 8>::with_min_align_and_capacity(64);
+    let capacity = bump.chunk_capacity();
+    bump.alloc(1_u8);
+    assert_eq!(bump.chunk_capacity(), capacity - 8);
+    bump.alloc([1_u8; 9]);
+    assert_eq!(bump.chunk_capacity(), capacity - 24);
+}
+
+#[test]
+fn min_align_zero_sized_types() {
+    let bump = Bump::<Global, 16>::with_min_align();
+    let p = bump.alloc(()) as *mut () as usize;
+    assert_eq!(p % 16, 0);
+    let p = bump.alloc([0_u128; 0]) as *mut [u128; 0] as usize;
+    assert_eq!(p % 16, 0);
+}
+
+#[test]
+fn min_align_dealloc_shrink_and_grow() {
+    let bump = Bump::<Global, 8>::with_min_align_and_capacity(1024);
+    let capacity = bump.chunk_capacity();
+
+    unsafe {
+        // Deallocating the last allocation keeps the finger aligned.
+        let layout = Layout::from_size_align(3, 1).unwrap();
+        let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+        (&bump).deallocate(p, layout);
+        assert_eq!(bump.chunk_capacity(), capacity);
+
+        // Shrinking only reclaims multiples of `MIN_ALIGN`.
+        let old = Layout::from_size_align(100, 1).unwrap();
+        let new = Layout::from_size_align(45, 1).unwrap();
+        let p = (&bump).allocate(old).unwrap().cast::<u8>();
+        let q = (&bump).shrink(p, old, new).unwrap().cast::<u8>();
+        assert_eq!(q.as_ptr() as usize % 8, 0);
+        assert_eq!(bump.chunk_capacity() % 8, 0);
+
+        // Growing in place keeps the data and the alignment.
+        q.as_ptr().write_bytes(7, 45);
+        let newer = Layout::from_size_align(61, 1).unwrap();
+        let r = (&bump).grow(q, new, newer).unwrap().cast::<u8>();
+        assert_eq!(r.as_ptr() as usize % 8, 0);
+        assert_eq!(bump.chunk_capacity() % 8, 0);
+        assert!(std::slice::from_raw_parts(r.as_ptr(), 45)
+            .iter()
+            .all(|&b| b == 7));
+    }
+}
+
+#[test]
+fn min_align_builder() {
+    let mut bump = Bump::builder().min_align::<4>().capacity(100).build();
+    for i in 0..1000_u16 {
+        let p = bump.alloc(i) as *mut u16 as usize;
+        assert_eq!(p % 4, 0);
+    }
+    bump.reset();
+    assert_eq!(bump.chunk_capacity() % 4, 0);
+}
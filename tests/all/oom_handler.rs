@@ -0,0 +1,103 @@
+use bumpalo::{Bump, OomAction, OomContext};
+use std::alloc::Layout;
+use std::cell::Cell;
+
+thread_local! {
+    static CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn calls() -> usize {
+    CALLS.with(|c| c.get())
+}
+
+/// Retry with the same limit a couple of times, then lift it.
+fn lift_limit_eventually(bump: &dyn OomContext, _layout: Layout) -> OomAction {
+    CALLS.with(|c| c.set(c.get() + 1));
+    if calls() == 3 {
+        bump.set_allocation_limit(None);
+    }
+    OomAction::Retry
+}
+
+#[test]
+fn retries_until_the_handler_makes_room() {
+    CALLS.with(|c| c.set(0));
+    let bump = Bump::new();
+    bump.set_allocation_limit(Some(0));
+    bump.set_oom_handler(lift_limit_eventually);
+
+    assert_eq!(*bump.alloc(7_u64), 7);
+    assert_eq!(calls(), 3);
+
+    // Fallible allocations don't call the handler.
+    bump.set_allocation_limit(Some(bump.allocated_bytes()));
+    assert!(bump.try_alloc([0_u8; 10_000]).is_err());
+    assert_eq!(calls(), 3);
+}
+
+#[test]
+fn handler_gets_the_failed_layout() {
+    let bump = Bump::new();
+    bump.set_allocation_limit(Some(0));
+    bump.set_oom_handler(|bump, layout| {
+        assert_eq!(layout, Layout::new::<[u32; 100]>());
+        bump.set_allocation_limit(None);
+        OomAction::Retry
+    });
+    bump.alloc([0_u32; 100]);
+}
+
+#[test]
+fn handler_sees_the_arena() {
+    let bump = Bump::new();
+    bump.alloc(1_u8);
+    bump.set_allocation_limit(Some(bump.allocated_bytes()));
+    bump.set_oom_handler(|bump, _| {
+        assert_eq!(bump.allocation_limit(), Some(bump.allocated_bytes()));
+        bump.set_allocation_limit(None);
+        OomAction::Retry
+    });
+    bump.alloc([0_u8; 100_000]);
+    assert_eq!(bump.allocation_limit(), None);
+}
+
+#[test]
+#[should_panic(expected = "out of memory")]
+fn panics_by_default() {
+    let bump = Bump::new();
+    bump.set_allocation_limit(Some(0));
+    bump.alloc(1_u8);
+}
+
+#[test]
+#[should_panic(expected = "out of memory")]
+fn handler_can_panic() {
+    let bump = Bump::new();
+    bump.set_allocation_limit(Some(0));
+    bump.set_oom_handler(|_, _| OomAction::Panic);
+    bump.alloc(1_u8);
+}
+
+#[cfg(feature = "collections")]
+#[test]
+fn collections_use_the_handler() {
+    use bumpalo::collections::Vec;
+
+    let bump = Bump::new();
+    bump.set_oom_handler(|bump, _| {
+        bump.set_allocation_limit(None);
+        OomAction::Retry
+    });
+
+    let mut v = Vec::with_capacity_in(4, &bump);
+    v.extend([1_u64, 2, 3, 4]);
+    bump.set_allocation_limit(Some(bump.allocated_bytes()));
+    for i in 5..=10_000 {
+        v.push(i);
+    }
+    assert!(v.iter().copied().eq(1..=10_000));
+
+    bump.set_allocation_limit(Some(bump.allocated_bytes()));
+    let zeroes = bumpalo::vec![in &bump; 0_u8; 1 << 20];
+    assert!(zeroes.iter().all(|&x| x == 0));
+}
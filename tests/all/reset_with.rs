@@ -0,0 +1,184 @@
+use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+use bumpalo::{Bump, RetainPolicy};
+use std::cell::Cell;
+use std::ptr::NonNull;
+
+/// An allocator that counts its allocations, and how many of them are live.
+#[derive(Default)]
+struct Counting {
+    total: Cell<usize>,
+    live: Cell<usize>,
+}
+
+unsafe impl Allocator for Counting {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.total.set(self.total.get() + 1);
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+/// Allocate enough to fill a few chunks, and return the first allocation.
+fn fill<A: Allocator>(bump: &Bump<A>) -> *mut u64 {
+    let first = bump.alloc(0_u64) as *mut u64;
+    for i in 0..10_000_u64 {
+        bump.alloc(i);
+    }
+    first
+}
+
+#[test]
+fn keep_all_refills_oldest_chunk_first() {
+    let counting = Counting::default();
+    {
+        let mut bump = Bump::new_in(&counting);
+        let first = fill(&bump);
+        let live = counting.live.get();
+        let total = counting.total.get();
+        let allocated_bytes = bump.allocated_bytes();
+        assert!(live > 1);
+
+        bump.reset_with(RetainPolicy::All);
+        assert_eq!(counting.live.get(), live);
+        assert_eq!(bump.allocated_bytes(), allocated_bytes);
+        assert_eq!(bump.iter_allocated_chunks().count(), 1);
+
+        // The same allocations land in the same places, without allocating
+        // any new chunks.
+        assert_eq!(fill(&bump), first);
+        assert_eq!(counting.total.get(), total);
+        assert_eq!(bump.allocated_bytes(), allocated_bytes);
+    }
+    assert_eq!(counting.live.get(), 0);
+}
+
+#[test]
+fn big_allocations_skip_small_spare_chunks() {
+    let counting = Counting::default();
+    let mut bump = Bump::new_in(&counting);
+    fill(&bump);
+    let total = counting.total.get();
+    bump.reset_with(RetainPolicy::All);
+
+    // Only the newest chunk is big enough for this.
+    let big = bump.alloc_slice_fill_copy(20_000, 0_u8);
+    assert_eq!(big.len(), 20_000);
+    assert_eq!(counting.total.get(), total);
+}
+
+#[test]
+fn keep_up_to() {
+    let counting = Counting::default();
+    let mut bump = Bump::new_in(&counting);
+    let first = bump.alloc(0_u64) as *mut u64;
+    let first_chunk_size = bump.allocated_bytes();
+    fill(&bump);
+    let allocated_bytes = bump.allocated_bytes();
+
+    bump.reset_with(RetainPolicy::UpTo(usize::MAX));
+    assert_eq!(bump.allocated_bytes(), allocated_bytes);
+
+    bump.reset_with(RetainPolicy::UpTo(first_chunk_size + 1));
+    assert_eq!(counting.live.get(), 1);
+    assert_eq!(bump.allocated_bytes(), first_chunk_size);
+    assert_eq!(bump.alloc(0_u64) as *mut u64, first);
+
+    bump.reset_with(RetainPolicy::UpTo(first_chunk_size - 1));
+    assert_eq!(counting.live.get(), 0);
+    assert_eq!(bump.allocated_bytes(), 0);
+}
+
+#[test]
+fn merge_reaches_a_steady_state() {
+    let counting = Counting::default();
+    let mut bump = Bump::new_in(&counting);
+    fill(&bump);
+    bump.reset_with(RetainPolicy::Merge);
+    assert_eq!(counting.live.get(), 1);
+
+    let total = counting.total.get();
+    for _ in 0..10 {
+        fill(&bump);
+        assert_eq!(bump.iter_allocated_chunks().count(), 1);
+        bump.reset_with(RetainPolicy::Merge);
+    }
+    assert_eq!(counting.total.get(), total);
+    assert_eq!(counting.live.get(), 1);
+}
+
+#[test]
+fn merge_accounts_for_scopes() {
+    let mut bump = Bump::new();
+    bump.alloc(1_u8);
+    bump.scope(|bump| {
+        bump.alloc_slice_fill_copy(100_000, 0_u8);
+    });
+    bump.alloc(2_u8);
+
+    bump.reset_with(RetainPolicy::Merge);
+    assert!(bump.allocated_bytes() >= 100_000);
+    assert!(bump.chunk_capacity() >= 100_000);
+}
+
+#[test]
+fn merge_accounts_for_scopes_across_chunks() {
+    let mut bump = Bump::with_capacity(1000);
+    for _ in 0..10 {
+        bump.alloc_slice_fill_copy(1000, 0_u8);
+    }
+    bump.scope(|bump| {
+        for _ in 0..10 {
+            bump.alloc_slice_fill_copy(1000, 0_u8);
+        }
+    });
+
+    // The scope's chunks were freed, but everything was in use at once.
+    bump.reset_with(RetainPolicy::Merge);
+    assert!(bump.chunk_capacity() >= 20 * 1000);
+}
+
+#[test]
+fn merge_respects_allocation_limit() {
+    let mut bump = Bump::builder().fixed_chunk_size(Some(12_000)).build();
+    bump.alloc_slice_fill_copy(10_000, 0_u8);
+    bump.alloc_slice_fill_copy(10_000, 0_u8);
+    bump.set_allocation_limit(Some(15_000));
+
+    // No chunk holds everything, and a new one would be over the limit.
+    bump.reset_with(RetainPolicy::Merge);
+    assert_eq!(bump.allocated_bytes(), 0);
+}
+
+#[test]
+fn spare_chunks_count_towards_allocation_limit() {
+    let mut bump = Bump::new();
+    fill(&bump);
+    bump.reset_with(RetainPolicy::All);
+    bump.set_allocation_limit(Some(bump.allocated_bytes()));
+
+    // Everything fits in the spare chunks again, but nothing more.
+    fill(&bump);
+    assert!(bump.try_alloc([0_u8; 100_000]).is_err());
+}
+
+#[test]
+fn upward_keep_all_refills_oldest_chunk_first() {
+    let mut bump = Bump::upward();
+    let first = bump.alloc(0_u64) as *mut u64;
+    for i in 0..10_000_u64 {
+        bump.alloc(i);
+    }
+    let chunks = bump.iter_allocated_chunks().count();
+
+    bump.reset_with(RetainPolicy::All);
+    assert_eq!(bump.alloc(0_u64) as *mut u64, first);
+    for i in 0..10_000_u64 {
+        bump.alloc(i);
+    }
+    assert_eq!(bump.iter_allocated_chunks().count(), chunks);
+}
@@ -0,0 +1,88 @@
+// These tests only find problems when run under AddressSanitizer or Valgrind,
+// which report any access to memory that is poisoned when it shouldn't be.
+
+use allocator_api2::alloc::Allocator;
+use bumpalo::{Bump, Global, RetainPolicy};
+use std::alloc::Layout;
+use std::mem::MaybeUninit;
+
+fn touch(ptr: *mut u8, len: usize) {
+    unsafe {
+        ptr.write_bytes(0xAB, len);
+        assert!(std::slice::from_raw_parts(ptr, len)
+            .iter()
+            .all(|&b| b == 0xAB));
+    }
+}
+
+fn exercise<const UP: bool>(mut bump: Bump<Global, 1, UP>) {
+    for size in [1, 3, 8, 100, 5000] {
+        let p = bump.alloc_layout(Layout::from_size_align(size, 1).unwrap());
+        touch(p.as_ptr(), size);
+    }
+    touch(
+        bump.alloc_slice_fill_copy(100_000, 0_u8).as_mut_ptr(),
+        100_000,
+    );
+
+    // Memory that was deallocated, or given back by shrinking, can be
+    // allocated again.
+    let layout = Layout::new::<[u8; 64]>();
+    let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+    unsafe { (&bump).deallocate(p, layout) };
+    let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+    touch(p.as_ptr(), 64);
+    let small = Layout::new::<[u8; 8]>();
+    let p = unsafe { (&bump).shrink(p, layout, small).unwrap().cast::<u8>() };
+    touch(p.as_ptr(), 8);
+    let p = unsafe { (&bump).grow(p, small, layout).unwrap().cast::<u8>() };
+    touch(p.as_ptr(), 64);
+
+    let _ = bump.alloc_try_with(|| Err::<[u8; 32], _>(()));
+    touch(bump.alloc([0_u8; 32]).as_mut_ptr(), 32);
+
+    bump.scope(|bump| {
+        touch(bump.alloc([0_u8; 256]).as_mut_ptr(), 256);
+    });
+    touch(bump.alloc([0_u8; 256]).as_mut_ptr(), 256);
+
+    bump.reset();
+    touch(bump.alloc([0_u8; 256]).as_mut_ptr(), 256);
+
+    bump.alloc_slice_fill_copy(100_000, 0_u8);
+    bump.reset_with(RetainPolicy::All);
+    touch(
+        bump.alloc_slice_fill_copy(100_000, 0_u8).as_mut_ptr(),
+        100_000,
+    );
+}
+
+#[test]
+fn allocated_memory_is_accessible() {
+    exercise(Bump::new());
+    exercise(Bump::upward());
+}
+
+#[test]
+fn buffers_are_accessible_again_after_use() {
+    let mut buffer = [MaybeUninit::<u8>::uninit(); 1024];
+    {
+        let bump = Bump::from_buffer(&mut buffer);
+        bump.alloc(1_u64);
+    }
+    touch(buffer.as_mut_ptr().cast(), buffer.len());
+}
+
+#[test]
+fn arenas_over_a_buffer_can_move() {
+    let mut buffer = [MaybeUninit::<u8>::uninit(); 1024];
+    let bump = Bump::from_buffer_in(&mut buffer, Global);
+    touch(bump.alloc([0_u8; 16]).as_mut_ptr(), 16);
+    let bump = Box::new(bump);
+    touch(bump.alloc([0_u8; 16]).as_mut_ptr(), 16);
+    touch(bump.alloc([0_u8; 2048]).as_mut_ptr(), 2048);
+    drop(bump);
+
+    // The buffer is usable again once the arena is done with it.
+    touch(buffer.as_mut_ptr().cast(), buffer.len());
+}
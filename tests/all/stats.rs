@@ -0,0 +1,122 @@
+use allocator_api2::alloc::{Allocator, Layout};
+use bumpalo::{Bump, RetainPolicy};
+
+#[test]
+fn counts_allocations_and_bytes() {
+    let bump = Bump::with_capacity(1024);
+    let stats = bump.stats();
+    assert_eq!(stats.allocations, 0);
+    assert_eq!(stats.chunks_allocated, 1);
+
+    bump.alloc(1_u8);
+    bump.alloc(2_u64);
+    bump.alloc_str("hello");
+
+    let stats = bump.stats();
+    assert_eq!(stats.allocations, 3);
+    assert_eq!(stats.bytes_requested, 1 + 8 + 5);
+    // The `u64` needed padding to be aligned after the `u8`.
+    assert_eq!(stats.bytes_consumed, 8 + 8 + 5);
+    assert_eq!(stats.slow_path_hits, 0);
+    assert_eq!(stats.high_water_mark, 8 + 8 + 5);
+}
+
+#[test]
+fn counts_chunks_and_slow_path_hits() {
+    let mut bump = Bump::new();
+    assert_eq!(bump.stats().chunks_allocated, 0);
+
+    for i in 0..10_000_u64 {
+        bump.alloc(i);
+    }
+    let chunks = bump.iter_allocated_chunks().count();
+    let stats = bump.stats();
+    assert_eq!(stats.chunks_allocated, chunks);
+    assert_eq!(stats.slow_path_hits, chunks);
+    assert_eq!(stats.chunks_freed, 0);
+    assert_eq!(stats.bytes_consumed, 80_000);
+
+    // Resetting keeps only the newest chunk.
+    bump.reset();
+    let stats = bump.stats();
+    assert_eq!(stats.chunks_freed, chunks - 1);
+    assert_eq!(stats.high_water_mark, 80_000);
+
+    // A spare chunk is refilled on the slow path, without allocating another.
+    bump.reset_with(RetainPolicy::All);
+    let stats = bump.stats();
+    assert_eq!(stats.chunks_allocated, chunks);
+    assert_eq!(stats.allocations, 10_000);
+}
+
+#[test]
+fn high_water_mark_survives_resets() {
+    let mut bump = Bump::new();
+    bump.alloc_slice_fill_copy(1000, 0_u8);
+    bump.reset();
+    bump.alloc_slice_fill_copy(10, 0_u8);
+    assert_eq!(bump.stats().high_water_mark, 1000);
+
+    bump.scope(|bump| {
+        bump.alloc_slice_fill_copy(5000, 0_u8);
+    });
+    assert_eq!(bump.stats().high_water_mark, 5010);
+}
+
+#[test]
+fn high_water_mark_counts_peaks_that_were_given_back() {
+    let bump = Bump::new();
+    let layout = Layout::from_size_align(1000, 1).unwrap();
+    let ptr = (&bump).allocate(layout).unwrap().cast::<u8>();
+    unsafe { (&bump).deallocate(ptr, layout) };
+    assert!(bump.stats().high_water_mark >= 1000);
+
+    let bump = Bump::new();
+    let ptr = (&bump).allocate(layout).unwrap().cast::<u8>();
+    unsafe {
+        let shrunk = Layout::from_size_align(10, 1).unwrap();
+        (&bump).shrink(ptr, layout, shrunk).unwrap();
+    }
+    assert!(bump.stats().high_water_mark >= 1000);
+
+    let bump = Bump::upward();
+    let ptr = (&bump).allocate(layout).unwrap().cast::<u8>();
+    unsafe {
+        let shrunk = Layout::from_size_align(10, 1).unwrap();
+        (&bump).shrink(ptr, layout, shrunk).unwrap();
+    }
+    assert!(bump.stats().high_water_mark >= 1000);
+}
+
+#[test]
+fn counts_bytes_reclaimed() {
+    let bump = Bump::new();
+    let layout = Layout::from_size_align(100, 4).unwrap();
+    let ptr = (&bump).allocate(layout).unwrap().cast::<u8>();
+    unsafe {
+        let shrunk = Layout::from_size_align(20, 4).unwrap();
+        let ptr = (&bump).shrink(ptr, layout, shrunk).unwrap().cast::<u8>();
+        assert_eq!(bump.stats().bytes_reclaimed, 80);
+
+        (&bump).deallocate(ptr, shrunk);
+    }
+    let stats = bump.stats();
+    assert_eq!(stats.bytes_reclaimed, 100);
+    assert_eq!(stats.bytes_requested, 100);
+    assert_eq!(stats.allocations, 1);
+}
+
+#[test]
+fn growing_in_place_is_not_another_allocation() {
+    let bump = Bump::upward();
+    let layout = Layout::from_size_align(16, 8).unwrap();
+    let ptr = (&bump).allocate(layout).unwrap().cast::<u8>();
+    let grown = Layout::from_size_align(64, 8).unwrap();
+    let new_ptr = unsafe { (&bump).grow(ptr, layout, grown).unwrap().cast::<u8>() };
+    assert_eq!(ptr, new_ptr);
+
+    let stats = bump.stats();
+    assert_eq!(stats.allocations, 1);
+    assert_eq!(stats.bytes_requested, 64);
+    assert_eq!(stats.bytes_consumed, 64);
+}
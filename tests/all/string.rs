@@ -1,5 +1,8 @@
 #![cfg(feature = "collections")]
-use bumpalo::{collections::String, format, Bump};
+use bumpalo::{
+    collections::{CollectionAllocErr, String},
+    format, try_format, AllocErrKind, Bump,
+};
 use std::fmt::Write;
 
 #[test]
@@ -18,6 +21,48 @@ fn trailing_comma_in_format_macro() {
     assert_eq!(v, "12");
 }
 
+#[test]
+fn format_macro_accepts_anything_format_args_does() {
+    let b = Bump::new();
+    let name = "bump";
+    assert_eq!(format!(in &b, "literal"), "literal");
+    assert_eq!(format!(in &b, "{name}"), "bump");
+    assert_eq!(format!(in &b, "{x}-{y:?}", x = 1, y = "z"), "1-\"z\"");
+    assert_eq!(format!(in &b, "{0}{0}{name}", 7,), "77bump");
+}
+
+#[test]
+#[should_panic(expected = "a formatting trait implementation returned an error")]
+fn format_macro_panics_on_formatting_errors() {
+    struct Fails;
+
+    impl std::fmt::Display for Fails {
+        fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            Err(std::fmt::Error)
+        }
+    }
+
+    let b = Bump::new();
+    format!(in &b, "{}", Fails);
+}
+
+#[test]
+fn try_format_macro() {
+    let b = Bump::new();
+    let name = "bump";
+    let s = try_format!(in &b, "{name} {}", 1).unwrap();
+    assert_eq!(s, "bump 1");
+    assert_eq!(try_format!(in &b, "literal",).unwrap(), "literal");
+
+    b.set_allocation_limit(Some(b.allocated_bytes()));
+    let big = name.repeat(1 << 16);
+    let err = try_format!(in &b, "{big}").unwrap_err();
+    assert!(matches!(
+        err,
+        CollectionAllocErr::AllocErr(e) if e.kind() == AllocErrKind::AllocationLimit
+    ));
+}
+
 #[test]
 fn push_str() {
     let b = Bump::new();
@@ -0,0 +1,86 @@
+use allocator_api2::alloc::{Allocator, Layout};
+use bumpalo::SyncBump;
+use std::thread;
+
+#[test]
+fn allocate_from_many_threads() {
+    let bump = SyncBump::new();
+    let per_thread: Vec<Vec<&u64>> = thread::scope(|s| {
+        let handles: Vec<_> = (0..8_u64)
+            .map(|t| {
+                let bump = &bump;
+                s.spawn(move || {
+                    (0..10_000)
+                        .map(|i| &*bump.alloc(t * 1_000_000 + i))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut addrs = vec![];
+    for (t, values) in per_thread.iter().enumerate() {
+        for (i, x) in values.iter().enumerate() {
+            assert_eq!(**x, t as u64 * 1_000_000 + i as u64);
+            assert_eq!(*x as *const u64 as usize % 8, 0);
+            addrs.push(*x as *const u64 as usize);
+        }
+    }
+    addrs.sort_unstable();
+    for w in addrs.windows(2) {
+        assert!(w[0] + 8 <= w[1]);
+    }
+    assert!(bump.allocated_bytes() >= 8 * 10_000 * 8);
+}
+
+#[test]
+fn slices_and_strs() {
+    let bump = SyncBump::with_capacity(16);
+    let xs = bump.alloc_slice_copy(&[1_u16, 2, 3]);
+    let s = bump.alloc_str("hello");
+    let big = bump.alloc_slice_copy(&[7_u8; 100_000]);
+    assert_eq!(xs, &[1, 2, 3]);
+    assert_eq!(s, "hello");
+    assert!(big.iter().all(|b| *b == 7));
+}
+
+#[test]
+fn reset_keeps_only_the_newest_chunk() {
+    let mut bump = SyncBump::new();
+    for i in 0..10_000_u64 {
+        bump.alloc(i);
+    }
+    let allocated_bytes = bump.allocated_bytes();
+    bump.reset();
+    assert!(bump.allocated_bytes() < allocated_bytes);
+
+    let before = bump.allocated_bytes();
+    let x = bump.alloc(1_u64) as *mut u64;
+    let y = bump.alloc(2_u64) as *mut u64;
+    assert_eq!(y as usize + 8, x as usize);
+    assert_eq!(bump.allocated_bytes(), before);
+}
+
+#[test]
+fn allocator_api() {
+    let bump = SyncBump::new();
+    let mut v = allocator_api2::vec::Vec::new_in(&bump);
+    for i in 0..1000_u32 {
+        v.push(i);
+    }
+    assert_eq!(v.iter().sum::<u32>(), 499_500);
+
+    unsafe {
+        // Deallocating the most recent allocation gives its space back.
+        let layout = Layout::new::<u64>();
+        let a = (&bump).allocate(layout).unwrap().cast::<u8>();
+        (&bump).deallocate(a, layout);
+        let b = (&bump).allocate(layout).unwrap().cast::<u8>();
+        assert_eq!(a, b);
+
+        // Shrinking keeps the allocation in place.
+        let c = (&bump).shrink(b, layout, Layout::new::<u32>()).unwrap();
+        assert_eq!(c.cast::<u8>(), b);
+    }
+}
@@ -219,4 +219,4 @@ fn miri_stacked_borrows_issue_247() {
     drop(unsafe { Box::from_raw_in(a, &bump) });
 
     let _b = Box::new_in(2u16, &bump);
-}
\ No newline at end of file
+}
@@ -116,3 +116,21 @@ fn try_alloc_try_with_large_enum_err() {
         .try_alloc_try_with(|| Result::<LargeEnum, _>::Err(()))
         .is_err());
 }
+
+// Unlike the tests above, this one is small enough to run in debug mode.
+#[test]
+fn try_alloc_try_with_err_in_new_chunk_leaves_it_empty() {
+    let mut b = Bump::with_capacity(64);
+
+    // The result doesn't fit in the first chunk, so it gets a new one.
+    assert!(matches!(
+        b.try_alloc_try_with(|| Result::<[u8; 4096], _>::Err(())),
+        Err(AllocOrInitError::Init(()))
+    ));
+    assert_eq!(b.iter_allocated_chunks().count(), 2);
+    assert_eq!(b.iter_allocated_chunks().next().unwrap().len(), 0);
+
+    // Its space is reused instead of leaked.
+    b.alloc([0_u8; 4096]);
+    assert_eq!(b.iter_allocated_chunks().count(), 2);
+}
@@ -0,0 +1,105 @@
+use crate::quickcheck;
+use bumpalo::TypedBump;
+use std::rc::Rc;
+
+quickcheck! {
+    fn iter_in_allocation_order(values: Vec<u32>) -> bool {
+        let mut arena = TypedBump::new();
+        for &v in &values {
+            arena.alloc(v);
+        }
+        arena.len() == values.len()
+            && arena.iter().eq(values.iter())
+            && arena.iter().rev().eq(values.iter().rev())
+            && arena.into_vec() == values
+    }
+}
+
+#[test]
+fn many_chunks() {
+    let mut arena = TypedBump::with_capacity(10);
+    for i in 0..100_000_u64 {
+        arena.alloc(i);
+    }
+    assert!(arena.iter().copied().eq(0..100_000));
+    for x in arena.iter_mut() {
+        *x *= 2;
+    }
+    assert!(arena.iter().copied().eq((0..100_000).map(|x| x * 2)));
+}
+
+#[test]
+fn iterating_from_both_ends_across_chunks() {
+    let mut arena = TypedBump::with_capacity(10);
+    for i in 0..1000_u32 {
+        arena.alloc(i);
+    }
+    let mut iter = arena.iter();
+    let mut front = 0;
+    let mut back = 1000;
+    while let Some(&x) = iter.next() {
+        assert_eq!(x, front);
+        front += 1;
+        match iter.next_back() {
+            Some(&y) => {
+                back -= 1;
+                assert_eq!(y, back);
+            }
+            None => break,
+        }
+    }
+    assert_eq!(front, back);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+}
+
+#[test]
+fn over_aligned_values() {
+    #[derive(Debug, PartialEq)]
+    #[repr(align(64))]
+    struct Aligned(u8);
+
+    let mut arena = TypedBump::new();
+    for i in 0..200 {
+        let x = arena.alloc(Aligned(i as u8)) as *mut Aligned;
+        assert_eq!(x as usize % 64, 0);
+    }
+    assert!(arena.iter().map(|a| a.0).eq((0..200).map(|i| i as u8)));
+}
+
+#[test]
+fn zero_sized_values() {
+    let mut arena = TypedBump::new();
+    for _ in 0..10 {
+        arena.alloc(());
+    }
+    assert_eq!(arena.iter().count(), 10);
+    assert_eq!(arena.into_vec().len(), 10);
+}
+
+#[test]
+fn drops_values() {
+    let shared = Rc::new(());
+    {
+        let arena = TypedBump::new();
+        for _ in 0..1000 {
+            arena.alloc(Rc::clone(&shared));
+        }
+        assert_eq!(Rc::strong_count(&shared), 1001);
+    }
+    assert_eq!(Rc::strong_count(&shared), 1);
+
+    let mut arena = TypedBump::new();
+    arena.alloc(Rc::clone(&shared));
+    arena.reset();
+    assert_eq!(Rc::strong_count(&shared), 1);
+    assert!(arena.is_empty());
+    arena.alloc(Rc::clone(&shared));
+    assert_eq!(arena.len(), 1);
+
+    // Values moved into a `Vec` are dropped by the `Vec`, and only once.
+    let values = arena.into_vec();
+    assert_eq!(Rc::strong_count(&shared), 2);
+    drop(values);
+    assert_eq!(Rc::strong_count(&shared), 1);
+}
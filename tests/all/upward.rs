@@ -0,0 +1,235 @@
+use crate::quickcheck;
+use allocator_api2::alloc::{Allocator, Global, Layout};
+use bumpalo::{Bump, UpBump};
+use std::ptr::NonNull;
+
+/// Allocate each of the given `(size, align)` pairs from a fresh upward
+/// bumping arena, fill each allocation with a distinct byte, and check that
+/// the allocations are aligned, don't overlap, and keep their contents.
+fn check_upward<const MIN_ALIGN: usize>(layouts: &[(usize, usize)]) {
+    let bump = UpBump::<Global, MIN_ALIGN>::with_min_align();
+    let mut regions = vec![];
+
+    for (i, &(size, align)) in layouts.iter().enumerate() {
+        let align = 1 << (align % 6);
+        let size = size % 512;
+        let layout = Layout::from_size_align(size, align).unwrap();
+        let ptr = bump.alloc_layout(layout).as_ptr();
+
+        assert_eq!(ptr as usize % align, 0);
+        assert_eq!(ptr as usize % MIN_ALIGN, 0);
+        unsafe { ptr.write_bytes(i as u8, size) };
+
+        regions.push((ptr as usize, size, i as u8));
+    }
+
+    for &(ptr, size, byte) in &regions {
+        let contents = unsafe { std::slice::from_raw_parts(ptr as *const u8, size) };
+        assert!(contents.iter().all(|b| *b == byte));
+    }
+
+    regions.sort();
+    for w in regions.windows(2) {
+        assert!(
+            w[0].0 + w[0].1 <= w[1].0,
+            "overlapping allocations: {:?}",
+            w
+        );
+    }
+}
+
+quickcheck! {
+    fn upward_allocations(layouts: Vec<(usize, usize)>) -> () {
+        check_upward::<1>(&layouts);
+    }
+
+    fn upward_allocations_min_align_8(layouts: Vec<(usize, usize)>) -> () {
+        check_upward::<8>(&layouts);
+    }
+}
+
+#[test]
+fn upward_allocations_ascend() {
+    let bump = Bump::upward();
+    let a = bump.alloc(1_u8) as *mut u8 as usize;
+    let b = bump.alloc(2_u8) as *mut u8 as usize;
+    let c = bump.alloc(3_u64) as *mut u64 as usize;
+    assert_eq!(b, a + 1);
+    assert!(c > b);
+    assert_eq!(c % 8, 0);
+}
+
+#[test]
+fn upward_grow_last_allocation_in_place() {
+    let bump = Bump::upward_with_capacity(1024);
+    let capacity = bump.chunk_capacity();
+    let layout = Layout::array::<u8>(8).unwrap();
+
+    unsafe {
+        let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+        p.as_ptr().write_bytes(7, 8);
+
+        let bigger = Layout::array::<u8>(64).unwrap();
+        let q = (&bump).grow(p, layout, bigger).unwrap().cast::<u8>();
+        assert_eq!(p, q);
+        assert_eq!(bump.chunk_capacity(), capacity - 64);
+        let contents = std::slice::from_raw_parts(q.as_ptr(), 8);
+        assert_eq!(contents, &[7; 8]);
+
+        // Not the last allocation anymore, so growing it has to move it.
+        bump.alloc(0_u8);
+        let biggest = Layout::array::<u8>(128).unwrap();
+        let r = (&bump).grow(q, bigger, biggest).unwrap().cast::<u8>();
+        assert_ne!(q, r);
+        let contents = std::slice::from_raw_parts(r.as_ptr(), 8);
+        assert_eq!(contents, &[7; 8]);
+    }
+}
+
+#[test]
+fn upward_grow_into_new_chunk() {
+    let bump = Bump::upward_with_capacity(64);
+    let layout = Layout::array::<u8>(8).unwrap();
+
+    unsafe {
+        let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+        p.as_ptr().write_bytes(7, 8);
+
+        let huge = Layout::array::<u8>(10_000).unwrap();
+        let q = (&bump).grow(p, layout, huge).unwrap().cast::<u8>();
+        assert_ne!(p, q);
+        let contents = std::slice::from_raw_parts(q.as_ptr(), 8);
+        assert_eq!(contents, &[7; 8]);
+    }
+}
+
+#[test]
+fn upward_shrink_and_dealloc() {
+    let bump = Bump::upward_with_capacity(1024);
+    let capacity = bump.chunk_capacity();
+    let layout = Layout::array::<u8>(100).unwrap();
+
+    unsafe {
+        let p = (&bump).allocate(layout).unwrap().cast::<u8>();
+        p.as_ptr().write_bytes(3, 100);
+        assert_eq!(bump.chunk_capacity(), capacity - 100);
+
+        let smaller = Layout::array::<u8>(10).unwrap();
+        let q = (&bump).shrink(p, layout, smaller).unwrap().cast::<u8>();
+        assert_eq!(p, q);
+        assert_eq!(bump.chunk_capacity(), capacity - 10);
+        let contents = std::slice::from_raw_parts(q.as_ptr(), 10);
+        assert_eq!(contents, &[3; 10]);
+
+        (&bump).deallocate(q, smaller);
+        assert_eq!(bump.chunk_capacity(), capacity);
+
+        // Deallocating anything but the last allocation is a no-op.
+        let a: NonNull<u8> = (&bump).allocate(smaller).unwrap().cast();
+        bump.alloc(0_u8);
+        (&bump).deallocate(a, smaller);
+        assert_eq!(bump.chunk_capacity(), capacity - 11);
+    }
+}
+
+#[test]
+fn upward_vec_grows_in_place() {
+    let bump = Bump::upward();
+    let mut v = allocator_api2::vec::Vec::new_in(&bump);
+    v.push(0_u64);
+    let start = v.as_ptr();
+    for i in 1..20 {
+        v.push(i);
+    }
+    assert_eq!(v.as_ptr(), start);
+    assert_eq!(v.iter().sum::<u64>(), 190);
+}
+
+#[test]
+fn upward_iter_allocated_chunks() {
+    let mut bump = Bump::upward();
+    bump.alloc(b'a');
+    bump.alloc(b'b');
+    bump.alloc(b'c');
+
+    let chunks: Vec<_> = bump.iter_allocated_chunks().collect();
+    assert_eq!(chunks.len(), 1);
+    let bytes: Vec<u8> = chunks[0]
+        .iter()
+        .map(|b| unsafe { b.assume_init() })
+        .collect();
+    assert_eq!(bytes, b"abc");
+
+    // Fill a couple more chunks, and check that all the bytes are accounted
+    // for.
+    for _ in 0..10_000 {
+        bump.alloc(b'x');
+    }
+    let total: usize = bump.iter_allocated_chunks().map(|c| c.len()).sum();
+    assert_eq!(total, 10_003);
+}
+
+#[test]
+fn upward_reset() {
+    let mut bump = Bump::upward();
+    for i in 0..10_000_u32 {
+        bump.alloc(i);
+    }
+    assert!(bump.iter_allocated_chunks().count() > 1);
+
+    bump.reset();
+    assert_eq!(bump.iter_allocated_chunks().count(), 1);
+    assert_eq!(bump.iter_allocated_chunks().next().unwrap().len(), 0);
+
+    let capacity = bump.chunk_capacity();
+    let x = bump.alloc(42_u32) as *mut u32;
+    let chunk = bump.iter_allocated_chunks().next().unwrap();
+    assert_eq!(chunk.as_ptr() as *mut u32, x);
+    assert_eq!(chunk.len(), 4);
+    assert_eq!(bump.chunk_capacity(), capacity - 4);
+}
+
+#[test]
+fn upward_alloc_try_with_rewinds() {
+    let mut bump = Bump::upward_with_capacity(1024);
+    let capacity = bump.chunk_capacity();
+    bump.alloc(1_u8);
+
+    let result: Result<&mut u64, ()> = bump.alloc_try_with(|| Err(()));
+    assert!(result.is_err());
+    assert_eq!(bump.chunk_capacity(), capacity - 1);
+
+    let result: Result<&mut [u8; 4096], ()> = bump.alloc_try_with(|| Err(()));
+    assert!(result.is_err());
+
+    // The result got a chunk of its own, which is empty again.
+    let chunks: Vec<_> = bump.iter_allocated_chunks().map(|c| c.len()).collect();
+    assert_eq!(chunks, [0, 1]);
+}
+
+#[test]
+fn upward_checkpoint() {
+    let mut bump = Bump::upward();
+    let x = bump.alloc(1_u32) as *mut u32;
+    let checkpoint = bump.checkpoint();
+    bump.alloc_slice_fill_copy(10_000, 0_u8);
+    unsafe { bump.reset_to(checkpoint) };
+    let y = bump.alloc(2_u32) as *mut u32;
+    assert_eq!(y as usize, x as usize + 4);
+
+    let sum = bump.scope(|bump| bump.alloc_slice_fill_with(100, |i| i).iter().sum::<usize>());
+    assert_eq!(sum, 4950);
+    assert_eq!(bump.alloc(3_u32) as *mut u32 as usize, y as usize + 4);
+}
+
+#[test]
+fn upward_builder() {
+    let bump = Bump::builder()
+        .upward()
+        .min_align::<8>()
+        .capacity(256)
+        .build();
+    let a = bump.alloc(1_u8) as *mut u8 as usize;
+    let b = bump.alloc(2_u8) as *mut u8 as usize;
+    assert_eq!(b, a + 8);
+}
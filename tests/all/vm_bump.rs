@@ -0,0 +1,121 @@
+use allocator_api2::alloc::Allocator;
+use bumpalo::{AllocErrKind, VmBump};
+use std::alloc::Layout;
+
+#[test]
+fn allocations_are_contiguous() {
+    let mut bump = VmBump::new();
+    let first = bump.alloc(0_u64) as *mut u64;
+    for i in 1..100_000_u64 {
+        let p = bump.alloc(i) as *mut u64;
+        assert_eq!(p, first.wrapping_add(i as usize));
+    }
+    bump.alloc_str("end");
+
+    let chunks: Vec<_> = bump.iter_allocated_chunks().collect();
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].as_ptr() as usize, first as usize);
+    assert_eq!(chunks[0].len(), 800_003);
+}
+
+#[test]
+fn pages_are_committed_as_needed() {
+    let bump = VmBump::with_reservation(16 << 20);
+    assert_eq!(bump.reserved_bytes(), 16 << 20);
+    assert_eq!(bump.allocated_bytes(), 0);
+
+    bump.alloc(1_u8);
+    let committed = bump.allocated_bytes();
+    assert!(committed > 0 && committed < 1 << 20);
+
+    bump.alloc_slice_copy(&[1_u8; 3 << 20]);
+    assert!(bump.allocated_bytes() > 3 << 20);
+    assert!(bump.allocated_bytes() <= bump.reserved_bytes());
+}
+
+#[test]
+fn allocating_past_the_reservation_fails() {
+    let bump = VmBump::with_reservation(1);
+    let reserved = bump.reserved_bytes();
+    assert!(bump
+        .try_alloc_layout(Layout::array::<u8>(reserved).unwrap())
+        .is_ok());
+    let err = bump.try_alloc(1_u8).unwrap_err();
+    assert_eq!(err.kind(), AllocErrKind::Allocator);
+}
+
+#[test]
+fn alignment_is_respected() {
+    let bump = VmBump::new();
+    bump.alloc(1_u8);
+    for align in [2, 8, 64, 4096] {
+        let p = bump.alloc_layout(Layout::from_size_align(3, align).unwrap());
+        assert_eq!(p.as_ptr() as usize % align, 0);
+    }
+}
+
+#[test]
+fn reset_gives_memory_back() {
+    let mut bump = VmBump::new();
+    let committed = {
+        let xs = bump.alloc_slice_copy(&[0xAB_u8; 100_000]);
+        assert!(xs.iter().all(|&x| x == 0xAB));
+        bump.allocated_bytes()
+    };
+
+    bump.reset();
+    assert_eq!(bump.used_bytes(), 0);
+    assert_eq!(bump.allocated_bytes(), committed);
+
+    // The memory was decommitted, so it reads as zeroes again.
+    let p = bump.alloc_layout(Layout::array::<u8>(100_000).unwrap());
+    let bytes = unsafe { std::slice::from_raw_parts(p.as_ptr(), 100_000) };
+    assert!(bytes.iter().all(|&b| b == 0));
+}
+
+#[test]
+fn last_allocation_grows_in_place() {
+    let bump = VmBump::new();
+    bump.alloc(1_u32);
+    let small = Layout::array::<u32>(4).unwrap();
+    let p = (&bump).allocate(small).unwrap().cast::<u8>();
+    unsafe { p.as_ptr().write_bytes(7, small.size()) };
+
+    let big = Layout::array::<u32>(1 << 20).unwrap();
+    let q = unsafe { (&bump).grow(p, small, big).unwrap().cast::<u8>() };
+    assert_eq!(p, q);
+    assert_eq!(unsafe { *q.as_ptr().add(small.size() - 1) }, 7);
+
+    let q = unsafe { (&bump).shrink(q, big, small).unwrap().cast::<u8>() };
+    assert_eq!(p, q);
+    unsafe { (&bump).deallocate(q, small) };
+    assert_eq!(bump.used_bytes(), 4);
+}
+
+#[test]
+fn other_allocations_are_copied_when_grown() {
+    let bump = VmBump::new();
+    let small = Layout::array::<u8>(4).unwrap();
+    let p = (&bump).allocate(small).unwrap().cast::<u8>();
+    unsafe { p.as_ptr().copy_from([1, 2, 3, 4].as_ptr(), 4) };
+    bump.alloc(0_u8);
+
+    let big = Layout::array::<u8>(8).unwrap();
+    let q = unsafe { (&bump).grow(p, small, big).unwrap().cast::<u8>() };
+    assert_ne!(p, q);
+    assert_eq!(
+        unsafe { std::slice::from_raw_parts(q.as_ptr(), 4) },
+        [1, 2, 3, 4]
+    );
+}
+
+#[test]
+fn huge_pages_can_be_requested() {
+    let bump = VmBump::new();
+    bump.set_huge_pages(true);
+    let xs = bump.alloc_slice_copy(&[1_u64; 1 << 16]);
+    assert!(xs.iter().all(|&x| x == 1));
+    assert_eq!(bump.allocated_bytes() % (2 << 20), 0);
+    bump.set_huge_pages(false);
+    bump.alloc(2_u64);
+}
@@ -1,7 +1,7 @@
-use bumpalo::{AllocOrInitError, Bump};
+use bumpalo::{AllocErrKind, AllocOrInitError, Bump};
 use rand::Rng;
 use std::alloc::{GlobalAlloc, Layout, System};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 /// A custom allocator that wraps the system allocator, but lets us force
 /// allocation failures for testing.
@@ -168,7 +168,11 @@ fn main() {
             || {
                 test_static_size_alloc(
                     |bump| assert!(bump.try_alloc(1u8).is_ok()),
-                    |bump| assert!(bump.try_alloc(1u8).is_err()),
+                    |bump| {
+                        let err = bump.try_alloc(1u8).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                        assert_eq!(err.layout(), Some(Layout::new::<u8>()));
+                    },
                 );
             },
         ),
@@ -203,15 +207,265 @@ fn main() {
                     |bump| {
                         assert!(matches!(
                             bump.try_alloc_try_with::<_, u8, _>(|| Err(())),
-                            Err(AllocOrInitError::Alloc(_))
+                            Err(AllocOrInitError::Alloc(e)) if e.kind() == AllocErrKind::Allocator
                         ));
                     },
                 );
             },
         ),
+        test!(
+            "test try_alloc_slice_copy with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_slice_copy(&[1u8, 2, 3]).unwrap(), [1, 2, 3]),
+                    |bump| {
+                        let err = bump.try_alloc_slice_copy(&[1u8, 2, 3]).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                        assert_eq!(err.layout(), Some(Layout::new::<[u8; 3]>()));
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_clone with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert!(bump.try_alloc_slice_clone(&[1u8, 2, 3]).is_ok()),
+                    |bump| {
+                        let err = bump.try_alloc_slice_clone(&[1u8, 2, 3]).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_str with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_str("hello").unwrap(), "hello"),
+                    |bump| {
+                        let err = bump.try_alloc_str("hello").unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_fill_with with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_slice_fill_with(3, |i| i as u8).unwrap(), [0, 1, 2]),
+                    |bump| {
+                        let err = bump.try_alloc_slice_fill_with(3, |i| i as u8).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_fill_copy with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_slice_fill_copy(3, 7u8).unwrap(), [7, 7, 7]),
+                    |bump| {
+                        let err = bump.try_alloc_slice_fill_copy(3, 7u8).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_fill_clone with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert!(bump.try_alloc_slice_fill_clone(3, &7u8).is_ok()),
+                    |bump| {
+                        let err = bump.try_alloc_slice_fill_clone(3, &7u8).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_fill_iter with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_slice_fill_iter(0u8..3).unwrap(), [0, 1, 2]),
+                    |bump| {
+                        let err = bump.try_alloc_slice_fill_iter(0u8..3).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_fill_default with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_slice_fill_default::<u8>(3).unwrap(), [0, 0, 0]),
+                    |bump| {
+                        let err = bump.try_alloc_slice_fill_default::<u8>(3).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_fill_with_result (Ok) with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| {
+                        let xs = bump.try_alloc_slice_fill_with_result::<_, (), _>(3, |i| Ok(i as u8));
+                        assert_eq!(xs.unwrap(), [0, 1, 2]);
+                    },
+                    |bump| {
+                        assert!(matches!(
+                            bump.try_alloc_slice_fill_with_result::<_, (), _>(3, |i| Ok(i as u8)),
+                            Err(AllocOrInitError::Alloc(e)) if e.kind() == AllocErrKind::Allocator
+                        ));
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_fill_with_result (Err) with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| {
+                        // The elements that were initialized before the error
+                        // are dropped.
+                        let value = std::rc::Rc::new(0u8);
+                        let result = bump.try_alloc_slice_fill_with_result(3, |i| {
+                            if i < 2 {
+                                Ok(value.clone())
+                            } else {
+                                Err(())
+                            }
+                        });
+                        assert!(matches!(result, Err(AllocOrInitError::Init(()))));
+                        assert_eq!(std::rc::Rc::strong_count(&value), 1);
+                    },
+                    |bump| {
+                        assert!(matches!(
+                            bump.try_alloc_slice_fill_with_result::<u8, _, _>(3, |_| Err(())),
+                            Err(AllocOrInitError::Alloc(e)) if e.kind() == AllocErrKind::Allocator
+                        ));
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_uninit_slice with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_uninit_slice::<u8>(3).unwrap().len(), 3),
+                    |bump| {
+                        let err = bump.try_alloc_uninit_slice::<u8>(3).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_zeroed_slice with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_zeroed_slice::<u8>(3).unwrap(), [0, 0, 0]),
+                    |bump| {
+                        let err = bump.try_alloc_zeroed_slice::<u8>(3).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_slice_from_iter with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| {
+                        let xs = bump.try_alloc_slice_from_iter((0u8..10).filter(|i| i % 2 == 0));
+                        assert_eq!(xs.unwrap(), [0, 2, 4, 6, 8]);
+                    },
+                    |bump| {
+                        // The elements produced before growing the slice
+                        // failed are dropped.
+                        static DROPS: AtomicUsize = AtomicUsize::new(0);
+                        #[derive(Debug)]
+                        struct Counted(#[allow(dead_code)] u64);
+                        impl Drop for Counted {
+                            fn drop(&mut self) {
+                                DROPS.fetch_add(1, Ordering::SeqCst);
+                            }
+                        }
+
+                        let err = bump
+                            .try_alloc_slice_from_iter((0..100).filter(|_| true).map(Counted))
+                            .unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                        assert!(DROPS.load(Ordering::SeqCst) > 0);
+                    },
+                );
+            },
+        ),
+        test!(
+            "test try_alloc_fmt with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bump.try_alloc_fmt(format_args!("{}{}", 1, 2)).unwrap(), "12"),
+                    |bump| {
+                        let err = bump.try_alloc_fmt(format_args!("{}{}", 1, 2)).unwrap_err();
+                        assert_eq!(err.kind(), AllocErrKind::Allocator);
+                    },
+                );
+            },
+        ),
+        test!("test TypedBump iterates and drops without allocating", || {
+            static DROPS: AtomicUsize = AtomicUsize::new(0);
+            struct Counted(u64);
+            impl Drop for Counted {
+                fn drop(&mut self) {
+                    DROPS.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+
+            let mut arena = GLOBAL_ALLOCATOR.with_successful_allocs(|| {
+                let arena = bumpalo::TypedBump::with_capacity(10);
+                for i in 0..10_000 {
+                    arena.alloc(Counted(i));
+                }
+                arena
+            });
+
+            GLOBAL_ALLOCATOR.with_alloc_failures(|| {
+                assert!(arena.iter().map(|c| c.0).eq(0..10_000));
+                assert!(arena.iter().rev().map(|c| c.0).eq((0..10_000).rev()));
+                for c in arena.iter_mut() {
+                    c.0 += 1;
+                }
+                assert!(arena.iter().map(|c| c.0).eq(1..10_001));
+                arena.reset();
+                assert_eq!(DROPS.load(Ordering::SeqCst), 10_000);
+                arena.alloc(Counted(0));
+                drop(arena);
+                assert_eq!(DROPS.load(Ordering::SeqCst), 10_001);
+            });
+        }),
+        #[cfg(feature = "collections")]
+        test!(
+            "test try_format! with and without global allocation failures",
+            || {
+                test_static_size_alloc(
+                    |bump| assert_eq!(bumpalo::try_format!(in bump, "{}{}", 1, 2).unwrap(), "12"),
+                    |bump| {
+                        let result = bumpalo::try_format!(in bump, "{}{}", 1, 2);
+                        assert!(result.is_err());
+                    },
+                );
+            },
+        ),
         #[cfg(feature = "collections")]
         test!("test Vec::try_reserve and Vec::try_reserve_exact", || {
-            use bumpalo::collections::Vec;
+            use bumpalo::collections::{CollectionAllocErr, Vec};
 
             let bump = Bump::try_new().unwrap();
 
@@ -224,7 +478,10 @@ fn main() {
                 assert!(vec.try_reserve_exact(chunk_cap).is_ok());
 
                 // Fails to allocate further since allocator returns null
-                assert!(vec.try_reserve(chunk_cap + 1).is_err());
+                assert!(matches!(
+                    vec.try_reserve(chunk_cap + 1),
+                    Err(CollectionAllocErr::AllocErr(e)) if e.kind() == AllocErrKind::Allocator
+                ));
                 assert!(vec.try_reserve_exact(chunk_cap + 1).is_err());
             });
 